@@ -0,0 +1,36 @@
+// Covers the screen for scene transitions.
+//
+// The cover is drawn by the main camera, so each canvas pixel is either covered or not.
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct Transition {
+    color: vec4<f32>,
+    // The canvas size, in pixels.
+    size: vec2<f32>,
+    // The center of the iris, in uv coordinates.
+    center: vec2<f32>,
+    // 0 is fully revealed, 1 is fully covered.
+    progress: f32,
+    // 0 fades, 1 closes an iris and 2 dissolves.
+    effect: u32,
+}
+@group(2) @binding(0) var<uniform> transition: Transition;
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let pixel = floor(in.uv * transition.size);
+
+    var coverage = transition.progress;
+    if transition.effect == 1u {
+        let radius = (1.0 - transition.progress) * length(transition.size);
+        coverage = step(radius, distance(pixel + 0.5, transition.center * transition.size));
+    } else if transition.effect == 2u {
+        coverage = select(0.0, 1.0, hash(pixel) < transition.progress);
+    }
+
+    return vec4<f32>(transition.color.rgb, transition.color.a * coverage);
+}
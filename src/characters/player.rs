@@ -15,6 +15,7 @@ use leafwing_input_manager::{
     prelude::{ActionState, InputMap},
     Actionlike,
 };
+use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 
 pub struct PlayerPlugin;
@@ -28,7 +29,10 @@ impl Plugin for PlayerPlugin {
         .add_systems(PreUpdate, init_camera)
         .add_systems(
             FixedUpdate,
-            ((walk, smooth_camera_offset).chain(), animate_cutscene),
+            (
+                (walk, smooth_camera_offset).chain(),
+                (stop_walking, animate_cutscene),
+            ),
         );
     }
 }
@@ -49,10 +53,20 @@ fn init_camera(
 
 const PLAYER_CAM_OFFSET: Vec2 = Vec2::new(TILE_SIZE, -TILE_SIZE);
 
+const PLAYER_SPEED: f32 = 80.0;
+const PLAYER_ACCELERATION: f32 = 1600.0;
+const PLAYER_DAMPING: f32 = 20.0;
+
 #[derive(Default, Component)]
 #[require(Izzy, AnimationController<PlayerAnimation>(animation_controller), Direction)]
 #[require(ActionState<Action>, InputMap<Action>(input_map))]
-#[require(TriggerLayer(|| TriggerLayer(0)), DynamicBody, Collider(collider))]
+#[require(
+    KinematicController,
+    Acceleration,
+    Collider(collider),
+    CollisionLayers(layers)
+)]
+#[require(LinearDamping(|| LinearDamping(PLAYER_DAMPING)), MaxSpeed(|| MaxSpeed(PLAYER_SPEED)))]
 #[require(CameraOffset(|| CameraOffset(PLAYER_CAM_OFFSET)))]
 #[require(YOrigin(|| YOrigin(-TILE_SIZE * 1.9)))]
 pub struct Player;
@@ -83,6 +97,13 @@ fn input_map() -> InputMap<Action> {
     .with_one_to_many(Action::Interact, [KeyCode::KeyE, KeyCode::Space])
 }
 
+fn layers() -> CollisionLayers {
+    CollisionLayers::new(
+        CollisionLayers::DEFAULT | CollisionLayers::PLAYER,
+        CollisionLayers::ALL,
+    )
+}
+
 fn collider() -> Collider {
     Collider::from_circle(Vec2::new(TILE_SIZE, -TILE_SIZE * 1.75), TILE_SIZE / 3.5)
 }
@@ -99,7 +120,9 @@ pub enum Action {
     Interact,
 }
 
-#[derive(Default, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect, Component)]
+#[derive(
+    Default, PartialEq, Eq, Hash, Clone, Copy, Debug, Reflect, Component, Serialize, Deserialize,
+)]
 pub enum Direction {
     Up,
     #[default]
@@ -150,15 +173,15 @@ fn walk(
     mut player: Query<
         (
             &ActionState<Action>,
-            &mut Transform,
+            &mut Acceleration,
             &mut AnimationController<PlayerAnimation>,
             &mut Direction,
         ),
         (With<Player>, Without<CutsceneMovement>),
     >,
-    time: Res<Time>,
 ) {
-    if let Ok((action_state, mut transform, mut animation, mut last_dir)) = player.get_single_mut()
+    if let Ok((action_state, mut acceleration, mut animation, mut last_dir)) =
+        player.get_single_mut()
     {
         let mut vel = Vec2::ZERO;
 
@@ -205,10 +228,17 @@ fn walk(
             animation.set_animation(PlayerAnimation::Idle(*last_dir));
         }
 
-        const PLAYER_SPEED: f32 = 80.0;
-        vel = vel.clamp_length_max(1.0) * PLAYER_SPEED;
-        transform.translation.x += vel.x * time.delta_secs();
-        transform.translation.y += vel.y * time.delta_secs();
+        acceleration.0 = vel.clamp_length_max(1.0) * PLAYER_ACCELERATION;
+    }
+}
+
+/// Cutscenes take over movement from [`walk`], which would otherwise leave the player walking.
+fn stop_walking(
+    mut player: Query<(&mut Velocity, &mut Acceleration), (With<Player>, Added<CutsceneMovement>)>,
+) {
+    for (mut velocity, mut acceleration) in player.iter_mut() {
+        velocity.0 = Vec2::ZERO;
+        acceleration.0 = Vec2::ZERO;
     }
 }
 
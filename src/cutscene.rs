@@ -7,6 +7,7 @@ use std::time::Duration;
 
 use crate::curves::IntoCurve;
 use crate::gfx::camera::CameraSystem;
+use crate::physics::prelude::{KinematicController, Velocity};
 use crate::textbox::prelude::*;
 
 pub struct CutscenePlugin;
@@ -34,6 +35,8 @@ pub struct CutsceneMovement;
 struct MovementClip<C> {
     timer: Timer,
     curve: C,
+    /// Drive a [`KinematicController`] along the curve instead of moving the [`Transform`].
+    kinematic: bool,
 }
 
 /// The instantaneous velocity resulting from cutscene movements.
@@ -69,6 +72,17 @@ where
         duration: Duration,
     ) -> impl IntoBox<C>;
 
+    /// Like [`CutsceneFragment::move_to`], but entities with a [`KinematicController`]
+    /// slide along walls rather than passing through them.
+    ///
+    /// Blocked entities may not reach `position`.
+    fn walk_to<M: Component>(
+        self,
+        marker: M,
+        position: Vec3,
+        duration: Duration,
+    ) -> impl IntoBox<C>;
+
     fn move_curve<M: Component, I>(
         self,
         marker: M,
@@ -93,24 +107,16 @@ where
         position: Vec3,
         duration: Duration,
     ) -> impl IntoBox<C> {
-        let system = move |q: Query<(Entity, &Transform), With<M>>,
-                           root: Single<&Transform, With<C>>,
-                           mut commands: Commands| {
-            let root = root.into_inner();
-            for (entity, transform) in q.iter() {
-                commands.entity(entity).insert((
-                    CutsceneMovement,
-                    CutsceneVelocity(Vec3::ZERO),
-                    MovementClip {
-                        curve: EaseFunction::Linear
-                            .into_curve(transform.translation, root.translation - position),
-                        timer: Timer::new(duration, TimerMode::Once),
-                    },
-                ));
-            }
-        };
+        self.on_start(linear_movement::<M, C>(position, duration, false))
+    }
 
-        self.on_start(system)
+    fn walk_to<M: Component>(
+        self,
+        _marker: M,
+        position: Vec3,
+        duration: Duration,
+    ) -> impl IntoBox<C> {
+        self.on_start(linear_movement::<M, C>(position, duration, true))
     }
 
     fn move_curve<M: Component, I>(
@@ -134,6 +140,7 @@ where
                     MovementClip {
                         curve: curve.into_curve(transform.translation, root.translation - position),
                         timer: Timer::new(duration, TimerMode::Once),
+                        kinematic: false,
                     },
                 ));
             }
@@ -166,6 +173,30 @@ where
     }
 }
 
+fn linear_movement<M: Component, C: Component>(
+    position: Vec3,
+    duration: Duration,
+    kinematic: bool,
+) -> impl Fn(Query<(Entity, &Transform), With<M>>, Single<&Transform, With<C>>, Commands) {
+    move |q: Query<(Entity, &Transform), With<M>>,
+          root: Single<&Transform, With<C>>,
+          mut commands: Commands| {
+        let root = root.into_inner();
+        for (entity, transform) in q.iter() {
+            commands.entity(entity).insert((
+                CutsceneMovement,
+                CutsceneVelocity(Vec3::ZERO),
+                MovementClip {
+                    curve: EaseFunction::Linear
+                        .into_curve(transform.translation, root.translation - position),
+                    timer: Timer::new(duration, TimerMode::Once),
+                    kinematic,
+                },
+            ));
+        }
+    }
+}
+
 #[derive(Default, Resource)]
 struct MovementSystemCache(HashSet<TypeId>);
 
@@ -207,24 +238,43 @@ fn apply_movements<C: Curve<Vec3> + Threaded>(
             &mut Transform,
             &mut MovementClip<C>,
             &mut CutsceneVelocity,
+            Option<&mut Velocity>,
+            Has<KinematicController>,
         ),
         With<CutsceneMovement>,
     >,
     time: Res<Time>,
     mut commands: Commands,
 ) {
-    for (entity, mut transform, mut clip, mut velocity) in q.iter_mut() {
+    for (entity, mut transform, mut clip, mut velocity, controller_velocity, has_controller) in
+        q.iter_mut()
+    {
         clip.tick(time.delta());
 
+        let mut controller_velocity =
+            controller_velocity.filter(|_| clip.kinematic && has_controller);
+
         if let Some(new_position) = clip.position() {
             let difference = new_position - transform.translation;
-            transform.translation = new_position;
+
+            match controller_velocity.as_mut() {
+                // Chase the curve, leaving walls to the physics schedule.
+                Some(controller_velocity) => {
+                    if time.delta_secs() > 0. {
+                        controller_velocity.0 = difference.xy() / time.delta_secs();
+                    }
+                }
+                None => transform.translation = new_position,
+            }
             velocity.0 = difference;
         }
 
         if clip.complete() {
             // Not sure if this is totally ideal
             velocity.0 = Vec3::ZERO;
+            if let Some(controller_velocity) = controller_velocity.as_mut() {
+                controller_velocity.0 = Vec2::ZERO;
+            }
             commands.entity(entity).remove::<MovementClip<C>>();
         }
     }
@@ -0,0 +1,155 @@
+use crate::reset::RegisterTransient;
+use bevy::prelude::*;
+use bevy_sequence::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(test)]
+mod tests;
+
+/// Tracks story progress in [`StoryFlags`].
+pub struct FlagsPlugin;
+
+impl Plugin for FlagsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut flags = StoryFlags::default();
+        flags.set(PETALS, STARTING_PETALS);
+        flags.persist(PETALS);
+
+        app.insert_resource(flags)
+            .register_transient_with(StoryFlags::reset);
+    }
+}
+
+/// How many petals the flower has left.
+pub const PETALS: &str = "petals";
+
+/// The flower is already missing a few petals at the moment of creation.
+pub const STARTING_PETALS: i64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlagValue {
+    Bool(bool),
+    Int(i64),
+    String(String),
+}
+
+impl From<bool> for FlagValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for FlagValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<i32> for FlagValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<&str> for FlagValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.into())
+    }
+}
+
+impl From<String> for FlagValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+/// Named story values that outlive scenes.
+///
+/// Unset flags read as `false`, `0` or an empty string. Flags are cleared when the world
+/// resets unless marked with [`StoryFlags::persist`].
+#[derive(Debug, Default, Clone, PartialEq, Resource, Serialize, Deserialize)]
+pub struct StoryFlags {
+    values: BTreeMap<String, FlagValue>,
+    persistent: BTreeSet<String>,
+}
+
+impl StoryFlags {
+    pub fn get(&self, key: &str) -> Option<&FlagValue> {
+        self.values.get(key)
+    }
+
+    pub fn bool(&self, key: &str) -> bool {
+        matches!(self.get(key), Some(FlagValue::Bool(true)))
+    }
+
+    pub fn int(&self, key: &str) -> i64 {
+        match self.get(key) {
+            Some(FlagValue::Int(value)) => *value,
+            _ => 0,
+        }
+    }
+
+    pub fn string(&self, key: &str) -> &str {
+        match self.get(key) {
+            Some(FlagValue::String(value)) => value,
+            _ => "",
+        }
+    }
+
+    /// Whether the flag equals `value`, treating unset flags as their default.
+    pub fn is(&self, key: &str, value: &FlagValue) -> bool {
+        match value {
+            FlagValue::Bool(value) => self.bool(key) == *value,
+            FlagValue::Int(value) => self.int(key) == *value,
+            FlagValue::String(value) => self.string(key) == value,
+        }
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<FlagValue>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    /// Adds `amount` to an int flag, overwriting flags of other types.
+    pub fn increment(&mut self, key: &str, amount: i64) {
+        let value = self.int(key) + amount;
+        self.set(key, value);
+    }
+
+    /// Keeps the flag across world resets.
+    pub fn persist(&mut self, key: impl Into<String>) {
+        self.persistent.insert(key.into());
+    }
+
+    fn reset(&mut self) {
+        let persistent = &self.persistent;
+        self.values.retain(|key, _| persistent.contains(key));
+    }
+}
+
+/// Whether the flag equals `value`.
+pub fn flag_is(key: &'static str, value: impl Into<FlagValue>) -> impl Fn(Res<StoryFlags>) -> bool {
+    let value = value.into();
+    move |flags: Res<StoryFlags>| flags.is(key, &value)
+}
+
+pub trait FlagFragment<D: Threaded, C: Threaded>: IntoFragment<D, C> + Sized {
+    /// Sets the flag once the fragment ends.
+    fn set_flag(self, key: &'static str, value: impl Into<FlagValue>) -> impl IntoFragment<D, C> {
+        let value = value.into();
+        self.on_end(move |mut flags: ResMut<StoryFlags>| flags.set(key, value.clone()))
+    }
+
+    /// Adds one to an int flag once the fragment ends.
+    fn increment_flag(self, key: &'static str) -> impl IntoFragment<D, C> {
+        self.on_end(move |mut flags: ResMut<StoryFlags>| flags.increment(key, 1))
+    }
+
+    /// Only starts the fragment while the flag equals `value`.
+    fn when_flag(self, key: &'static str, value: impl Into<FlagValue>) -> impl IntoFragment<D, C> {
+        let value = value.into();
+        self.eval_id(move |In(_): In<FragmentId>, flags: Res<StoryFlags>| flags.is(key, &value))
+    }
+}
+
+impl<D: Threaded, C: Threaded, T> FlagFragment<D, C> for T where T: IntoFragment<D, C> {}
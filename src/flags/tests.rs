@@ -0,0 +1,149 @@
+use super::*;
+use bevy_sequence::fragment::DataLeaf;
+
+#[test]
+fn unset_flags_read_as_defaults() {
+    let flags = StoryFlags::default();
+
+    assert_eq!(flags.get("door"), None);
+    assert!(!flags.bool("door"));
+    assert_eq!(flags.int("door"), 0);
+    assert_eq!(flags.string("door"), "");
+
+    assert!(flags.is("door", &false.into()));
+    assert!(flags.is("door", &0.into()));
+    assert!(flags.is("door", &"".into()));
+    assert!(!flags.is("door", &true.into()));
+}
+
+#[test]
+fn flags_read_as_their_type() {
+    let mut flags = StoryFlags::default();
+    flags.set("door", true);
+    flags.set("petals", 4);
+    flags.set("name", "Izzy");
+
+    assert!(flags.bool("door"));
+    assert_eq!(flags.int("petals"), 4);
+    assert_eq!(flags.string("name"), "Izzy");
+
+    // Flags of another type read as the default.
+    assert_eq!(flags.int("door"), 0);
+    assert!(!flags.bool("petals"));
+    assert_eq!(flags.string("petals"), "");
+    assert!(flags.is("name", &false.into()));
+}
+
+#[test]
+fn increment_overwrites_other_types() {
+    let mut flags = StoryFlags::default();
+    flags.increment("petals", 2);
+    flags.increment("petals", 3);
+    assert_eq!(flags.get("petals"), Some(&FlagValue::Int(5)));
+
+    flags.set("door", true);
+    flags.increment("door", 1);
+    assert_eq!(flags.get("door"), Some(&FlagValue::Int(1)));
+
+    flags.set("name", "Izzy");
+    flags.increment("name", -1);
+    assert_eq!(flags.get("name"), Some(&FlagValue::Int(-1)));
+}
+
+#[test]
+fn reset_keeps_persistent_flags() {
+    let mut flags = StoryFlags::default();
+    flags.set("door", true);
+    flags.set("spring_water", true);
+    flags.persist("spring_water");
+    // Persisting a flag before it is set still keeps it.
+    flags.persist("petals");
+    flags.set("petals", 4);
+
+    flags.reset();
+    assert_eq!(flags.get("door"), None);
+    assert!(flags.bool("spring_water"));
+    assert_eq!(flags.int("petals"), 4);
+
+    // Persistence outlives the reset.
+    flags.set("spring_water", false);
+    flags.reset();
+    assert_eq!(flags.get("spring_water"), Some(&FlagValue::Bool(false)));
+}
+
+/// A fragment that ends as soon as it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Line(u8);
+
+/// Every [`Line`] that started.
+#[derive(Debug, Default, Resource)]
+struct Spoken(Vec<u8>);
+
+fn speak(
+    mut reader: EventReader<FragmentEvent<Line>>,
+    mut writer: EventWriter<FragmentEndEvent>,
+    mut spoken: ResMut<Spoken>,
+) {
+    for event in reader.read() {
+        spoken.0.push(event.data.0);
+        writer.send(event.end());
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, bevy_sequence::SequencePlugin))
+        .init_resource::<StoryFlags>()
+        .init_resource::<Spoken>()
+        .add_systems(Update, speak);
+    app
+}
+
+fn line(n: u8) -> DataLeaf<Line> {
+    DataLeaf::new(Line(n))
+}
+
+#[test]
+fn set_flag_once_the_fragment_ends() {
+    let mut app = app();
+    spawn_root(
+        (
+            line(0),
+            line(1).set_flag("door", true),
+            line(2).increment_flag("petals"),
+        )
+            .once(),
+        &mut app.world_mut().commands(),
+    );
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    assert_eq!(app.world().resource::<Spoken>().0, [0, 1, 2]);
+    let flags = app.world().resource::<StoryFlags>();
+    assert!(flags.bool("door"));
+    assert_eq!(flags.int("petals"), 1);
+}
+
+#[test]
+fn when_flag_chooses_between_fragments() {
+    let mut app = app();
+    app.world_mut()
+        .resource_mut::<StoryFlags>()
+        .set("door", true);
+    spawn_root(
+        line(0).when_flag("door", false).once(),
+        &mut app.world_mut().commands(),
+    );
+    spawn_root(
+        line(1).when_flag("door", true).once(),
+        &mut app.world_mut().commands(),
+    );
+
+    for _ in 0..4 {
+        app.update();
+    }
+
+    assert_eq!(app.world().resource::<Spoken>().0, [1]);
+}
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 pub mod camera;
 pub mod pixel_perfect;
 pub mod post_processing;
+pub mod transition;
 pub mod zorder;
 
 pub struct GfxPlugin;
@@ -13,6 +14,7 @@ impl Plugin for GfxPlugin {
             camera::CameraPlugin,
             zorder::ZOrderPlugin,
             pixel_perfect::PixelPerfectPlugin,
+            transition::TransitionPlugin,
             bevy_light_2d::prelude::Light2dPlugin,
         ));
     }
@@ -0,0 +1,293 @@
+use super::camera::{CameraSystem, MainCamera};
+use crate::cutscene::{CutsceneMovement, CutsceneVelocity};
+use crate::player::Player;
+use crate::save::SaveCommands;
+use crate::scenes::{replace_active_scene, ActiveScene, SceneId};
+use crate::{HEIGHT, WIDTH};
+use bevy::ecs::system::SystemChangeTick;
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef, ShaderType};
+use bevy::sprite::{AlphaMode2d, Material2d, Material2dPlugin};
+
+/// How long covering, and then revealing, the screen takes.
+const TRANSITION_SECS: f32 = 0.5;
+
+/// Draws the screen covers used to switch scenes.
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(Material2dPlugin::<TransitionMaterial>::default())
+            .add_systems(
+                PostUpdate,
+                (update_transitions, release_claimed_players, lock_players)
+                    .chain()
+                    .after(CameraSystem::UpdateCamera)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransitionEffect {
+    Fade(Color),
+    /// Closes a circle on the player.
+    Iris(Color),
+    /// Covers the canvas pixel by pixel.
+    Dissolve(Color),
+}
+
+impl Default for TransitionEffect {
+    fn default() -> Self {
+        Self::Fade(Color::BLACK)
+    }
+}
+
+impl TransitionEffect {
+    fn uniform(&self) -> TransitionUniform {
+        let (color, effect) = match *self {
+            Self::Fade(color) => (color, 0),
+            Self::Iris(color) => (color, 1),
+            Self::Dissolve(color) => (color, 2),
+        };
+
+        TransitionUniform {
+            color: color.to_linear().to_vec4(),
+            size: Vec2::new(WIDTH, HEIGHT),
+            center: Vec2::splat(0.5),
+            progress: 0.,
+            effect,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Covering,
+    Covered,
+    Revealing,
+}
+
+/// A screen cover that follows the [`MainCamera`].
+///
+/// Players are locked from the moment the cover appears until it is revealed.
+#[derive(Component)]
+pub struct ScreenTransition {
+    phase: Phase,
+    timer: Timer,
+    /// Switched to once the screen is covered, and then revealed once its root exists.
+    target: Option<SceneId>,
+}
+
+/// Marks players locked by a [`ScreenTransition`], rather than by a cutscene.
+#[derive(Component)]
+struct TransitionLock;
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct TransitionMaterial {
+    #[uniform(0)]
+    transition: TransitionUniform,
+}
+
+#[derive(ShaderType, Debug, Clone)]
+struct TransitionUniform {
+    color: Vec4,
+    size: Vec2,
+    center: Vec2,
+    progress: f32,
+    effect: u32,
+}
+
+impl Material2d for TransitionMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/transition.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+pub trait TransitionCommands {
+    /// Covers the screen, switches to `target`, then reveals the new scene.
+    fn transition_to(&mut self, target: SceneId, effect: TransitionEffect);
+
+    /// Immediately covers the screen until [`TransitionCommands::reveal_screen`].
+    fn cover_screen(&mut self, effect: TransitionEffect);
+
+    /// Reveals screens covered with [`TransitionCommands::cover_screen`].
+    fn reveal_screen(&mut self);
+}
+
+impl TransitionCommands for Commands<'_, '_> {
+    fn transition_to(&mut self, target: SceneId, effect: TransitionEffect) {
+        self.queue(spawn_transition(effect, Phase::Covering, Some(target)));
+    }
+
+    fn cover_screen(&mut self, effect: TransitionEffect) {
+        self.queue(spawn_transition(effect, Phase::Covered, None));
+    }
+
+    fn reveal_screen(&mut self) {
+        self.queue(|world: &mut World| {
+            let mut transitions = world.query::<&mut ScreenTransition>();
+            for mut transition in transitions.iter_mut(world) {
+                if transition.phase == Phase::Covered && transition.target.is_none() {
+                    transition.phase = Phase::Revealing;
+                    transition.timer.reset();
+                }
+            }
+        });
+    }
+}
+
+fn spawn_transition(
+    effect: TransitionEffect,
+    phase: Phase,
+    target: Option<SceneId>,
+) -> impl FnOnce(&mut World) {
+    move |world: &mut World| {
+        let mut transition = effect.uniform();
+        if phase == Phase::Covered {
+            transition.progress = 1.;
+        }
+
+        let mesh = world.add_asset::<Mesh>(Rectangle::new(WIDTH, HEIGHT));
+        let material = world.add_asset::<TransitionMaterial>(TransitionMaterial { transition });
+
+        world.spawn((
+            ScreenTransition {
+                phase,
+                timer: Timer::from_seconds(TRANSITION_SECS, TimerMode::Once),
+                target,
+            },
+            Mesh2d(mesh),
+            MeshMaterial2d(material),
+            Transform::from_xyz(0., 0., 999.),
+        ));
+    }
+}
+
+fn update_transitions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut transitions: Query<(
+        Entity,
+        &mut ScreenTransition,
+        &mut Transform,
+        &MeshMaterial2d<TransitionMaterial>,
+    )>,
+    mut materials: ResMut<Assets<TransitionMaterial>>,
+    camera: Single<&Transform, (With<MainCamera>, Without<ScreenTransition>)>,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+    locked: Query<Entity, With<TransitionLock>>,
+    scenes: Query<&ActiveScene>,
+) {
+    for (entity, mut transition, mut transform, material) in transitions.iter_mut() {
+        match transition.phase {
+            Phase::Covering => {
+                if transition.timer.tick(time.delta()).finished() {
+                    // The new scene spawns under the cover.
+                    if let Some(target) = transition.target.clone() {
+                        commands.queue(switch_scene(entity, target));
+                    }
+                    transition.phase = Phase::Covered;
+                    transition.timer.reset();
+                }
+            }
+            Phase::Covered => {
+                let spawned = transition
+                    .target
+                    .as_ref()
+                    .is_some_and(|target| scenes.iter().any(|scene| scene.0 == *target));
+                if spawned {
+                    transition.target = None;
+                    transition.phase = Phase::Revealing;
+                }
+            }
+            Phase::Revealing => {
+                if transition.timer.tick(time.delta()).finished() {
+                    commands.entity(entity).despawn_recursive();
+                    for player in locked.iter() {
+                        commands
+                            .entity(player)
+                            .remove::<(TransitionLock, CutsceneMovement, CutsceneVelocity)>();
+                    }
+                }
+            }
+        }
+
+        let progress = match transition.phase {
+            Phase::Covering => transition.timer.fraction(),
+            Phase::Covered => 1.,
+            Phase::Revealing => 1. - transition.timer.fraction(),
+        };
+
+        // The canvas is pixel aligned with the main camera.
+        transform.translation = camera.translation.xy().extend(transform.translation.z);
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.transition.progress = progress;
+
+            if let Some(player) = &player {
+                let offset = player.translation().xy() - camera.translation.xy();
+                material.transition.center =
+                    Vec2::new(0.5 + offset.x / WIDTH, 0.5 - offset.y / HEIGHT);
+            }
+        }
+    }
+}
+
+/// Replaces the active scene with `target`, revealing the old scene if it cannot be spawned.
+fn switch_scene(entity: Entity, target: SceneId) -> impl FnOnce(&mut World) {
+    move |world: &mut World| match replace_active_scene(world, &target) {
+        Ok(()) => world.commands().save_game(),
+        Err(e) => {
+            error!("failed to switch scene: {e}");
+            if let Some(mut transition) = world.get_mut::<ScreenTransition>(entity) {
+                transition.target = None;
+                transition.phase = Phase::Revealing;
+            }
+        }
+    }
+}
+
+/// Hands players over to cutscenes that lock or move them while the screen is covered, so
+/// revealing the screen leaves the cutscene's lock in place.
+fn release_claimed_players(
+    mut commands: Commands,
+    players: Query<(Entity, Ref<TransitionLock>, Ref<CutsceneMovement>)>,
+    ticks: SystemChangeTick,
+) {
+    for (player, lock, movement) in players.iter() {
+        // The lock is never changed after it is inserted alongside the movement.
+        if movement
+            .last_changed()
+            .is_newer_than(lock.last_changed(), ticks.this_run())
+        {
+            commands.entity(player).remove::<TransitionLock>();
+        }
+    }
+}
+
+/// Takes control from players while a transition is active, including players spawned by the
+/// new scene.
+///
+/// Players already in a cutscene are left to it.
+fn lock_players(
+    mut commands: Commands,
+    transitions: Query<(), With<ScreenTransition>>,
+    players: Query<Entity, (With<Player>, Without<CutsceneMovement>)>,
+) {
+    if transitions.is_empty() {
+        return;
+    }
+
+    for player in players.iter() {
+        commands.entity(player).insert((
+            TransitionLock,
+            CutsceneMovement,
+            CutsceneVelocity(Vec3::ZERO),
+        ));
+    }
+}
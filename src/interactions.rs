@@ -1,41 +1,125 @@
-use crate::annual::Interactions;
+use crate::annual::{Interactions, TriggerVolumes};
+use crate::flags::{StoryFlags, PETALS};
 use crate::physics::prelude::*;
-use crate::player::{Action, Player};
+use crate::player::{Action, Direction, Player};
+use crate::save::CompletedFragments;
 use crate::{CutsceneMovement, TILE_SIZE};
 use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy_ldtk_scene::{EntityIid, EntitySize};
 use bevy_sequence::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
 
 /// Handles behavior associated with interaction dialogue.
 ///
-/// That is, entities that will create dialogue when interacted with.
+/// That is, entities that will create dialogue when interacted with, or when the player
+/// walks into them.
 pub struct InteractionPlugin;
 
 impl Plugin for InteractionPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<InteractionTrigger>()
-            .add_systems(Update, (insert_interaction_source, handle_interactions));
+            .add_event::<TriggerVolumeEntered>()
+            .add_systems(
+                Update,
+                (
+                    insert_interaction_source,
+                    (select_interaction, handle_interactions).chain(),
+                    insert_trigger_volume_source,
+                    handle_trigger_volumes,
+                ),
+            );
     }
 }
 
 pub trait BindInteraction<D: Threaded, C>: IntoFragment<D, C> + Sized {
     fn interaction(self, interaction: Interactions) -> impl IntoFragment<D, C> {
+        self.eval_id(interacted(interaction))
+    }
+
+    /// Like [`BindInteraction::interaction`], but only starts the fragment while `condition`
+    /// holds.
+    ///
+    /// Bind several fragments to the same interaction with exclusive conditions to choose
+    /// dialogue based on game state, such as `flag_is(key, false)` and `flag_is(key, true)`,
+    /// or any of the other conditions below.
+    fn interaction_when<M>(
+        self,
+        interaction: Interactions,
+        condition: impl Condition<M>,
+    ) -> impl IntoFragment<D, C> {
         self.eval_id(
-            move |In(id): In<FragmentId>,
-                  mut reader: EventReader<InteractionTrigger>,
-                  fragments: Query<&FragmentState>| {
-                reader.read().any(|e| e.0 == interaction)
-                    && fragments
-                        .get(id.entity())
-                        .ok()
-                        .is_none_or(|state| state.active_events.is_empty())
-            },
+            IntoSystem::into_system(interacted(interaction))
+                .and((|In(_): In<FragmentId>| {}).pipe(condition)),
         )
     }
 }
 
 impl<D: Threaded, C, T> BindInteraction<D, C> for T where T: IntoFragment<D, C> {}
 
+// Conditions over game state for choosing dialogue with `interaction_when`.
+#[allow(unused)]
+pub use crate::{flags::flag_is, inventory::has_item, reset::looped, scenes::visited};
+
+/// Whether the flower has at least `count` petals left.
+#[allow(unused)]
+pub fn petals_at_least(count: i64) -> impl Fn(Res<StoryFlags>) -> bool {
+    move |flags: Res<StoryFlags>| flags.int(PETALS) >= count
+}
+
+/// Whether the player just interacted with `interaction` and the fragment is free to start.
+fn interacted(
+    interaction: Interactions,
+) -> impl Fn(In<FragmentId>, EventReader<InteractionTrigger>, Query<&FragmentState>) -> bool {
+    move |In(id): In<FragmentId>,
+          mut reader: EventReader<InteractionTrigger>,
+          fragments: Query<&FragmentState>| {
+        reader.read().any(|e| e.0 == interaction)
+            && fragments
+                .get(id.entity())
+                .ok()
+                .is_none_or(|state| state.active_events.is_empty())
+    }
+}
+
+pub trait BindTriggerVolume<D: Threaded, C>: IntoFragment<D, C> + Sized {
+    /// Starts the fragment when the player walks into a trigger volume.
+    fn on_enter(self, volume: TriggerVolumes) -> impl IntoFragment<D, C> {
+        self.eval_id(entered(volume))
+    }
+
+    /// Like [`BindTriggerVolume::on_enter`], but only starts the fragment while `condition`
+    /// holds.
+    #[allow(unused)]
+    fn on_enter_when<M>(
+        self,
+        volume: TriggerVolumes,
+        condition: impl Condition<M>,
+    ) -> impl IntoFragment<D, C> {
+        self.eval_id(
+            IntoSystem::into_system(entered(volume))
+                .and((|In(_): In<FragmentId>| {}).pipe(condition)),
+        )
+    }
+}
+
+impl<D: Threaded, C, T> BindTriggerVolume<D, C> for T where T: IntoFragment<D, C> {}
+
+/// Whether the player just walked into `volume` and the fragment is free to start.
+fn entered(
+    volume: TriggerVolumes,
+) -> impl Fn(In<FragmentId>, EventReader<TriggerVolumeEntered>, Query<&FragmentState>) -> bool {
+    move |In(id): In<FragmentId>,
+          mut reader: EventReader<TriggerVolumeEntered>,
+          fragments: Query<&FragmentState>| {
+        reader.read().any(|e| e.0 == volume)
+            && fragments
+                .get(id.entity())
+                .ok()
+                .is_none_or(|state| state.active_events.is_empty())
+    }
+}
+
 /// The source of the interaction.
 #[derive(Component)]
 #[require(Transform)]
@@ -46,37 +130,232 @@ struct InteractionTrigger(Interactions);
 
 fn insert_interaction_source(
     mut commands: Commands,
-    entities: Query<(Entity, &crate::annual::Interaction), Added<crate::annual::Interaction>>,
+    entities: Query<
+        (Entity, &crate::annual::Interaction, &EntitySize),
+        Added<crate::annual::Interaction>,
+    >,
 ) {
-    for (entity, interaction) in entities.iter() {
+    for (entity, interaction, size) in entities.iter() {
+        let offset = Vec2::new(interaction.offset_x, -interaction.offset_y);
+        let center = Vec2::new(TILE_SIZE, -TILE_SIZE) + offset;
+
+        // The LDtk bounds take precedence, then a rect when both sides are given, then the
+        // radius.
+        let collider = if interaction.use_bounds {
+            instance_bounds(size, offset)
+        } else if interaction.width > 0. && interaction.height > 0. {
+            let size = Vec2::new(interaction.width, interaction.height);
+            Collider::from_rect(center - size / 2., size)
+        } else {
+            Collider::from_circle(center, interaction.radius)
+        };
+
         commands.entity(entity).insert((
             InteractionTriggerSource(interaction.interactions),
-            Trigger(Collider::from_circle(
-                Vec2::new(TILE_SIZE, -TILE_SIZE),
-                TILE_SIZE,
-            )),
+            Trigger(collider),
+            CollisionLayers::player_only(),
         ));
     }
 }
 
-fn handle_interactions(
+/// The rect covered by a resizable LDtk entity, which is pivoted at its top left.
+fn instance_bounds(size: &EntitySize, offset: Vec2) -> Collider {
+    Collider::from_rect(Vec2::new(0., -size.0.y) + offset, size.0)
+}
+
+/// How many pixels of distance facing an interaction source directly makes up for.
+const FACING_WEIGHT: f32 = TILE_SIZE;
+
+/// Marks the interaction source the player would interact with.
+#[derive(Component)]
+struct SelectedInteraction;
+
+/// Shown above the [`SelectedInteraction`].
+#[derive(Component)]
+struct InteractionPrompt;
+
+fn center(collider: &AbsoluteCollider) -> Vec2 {
+    Vec2::new(
+        (collider.min_x() + collider.max_x()) / 2.,
+        (collider.min_y() + collider.max_y()) / 2.,
+    )
+}
+
+fn interaction_prompt(server: &AssetServer, trigger: &Trigger) -> impl Bundle {
+    let bounds = trigger.0.absolute(&GlobalTransform::IDENTITY);
+
+    (
+        InteractionPrompt,
+        Sprite {
+            image: server.load("sprites/interaction_prompt.png"),
+            anchor: Anchor::BottomCenter,
+            ..Default::default()
+        },
+        Transform::from_xyz(center(&bounds).x, bounds.max_y() + 2., 1.),
+    )
+}
+
+/// Selects the interaction source in range that is closest to, and most in front of, the
+/// player.
+fn select_interaction(
+    mut commands: Commands,
+    server: Res<AssetServer>,
     player: Option<
-        Single<(Entity, &ActionState<Action>), (With<Player>, Without<CutsceneMovement>)>,
+        Single<
+            (Entity, &GlobalTransform, &Collider, &Direction),
+            (With<Player>, Without<CutsceneMovement>),
+        >,
+    >,
+    sources: Query<
+        (Entity, &GlobalTransform, &Trigger, Has<SelectedInteraction>),
+        With<InteractionTriggerSource>,
     >,
-    interactions: Query<&InteractionTriggerSource>,
-    mut reader: EventReader<TriggerEvent>,
+    prompts: Query<(Entity, &Parent), With<InteractionPrompt>>,
+    contacts: Res<Contacts>,
+) {
+    let selected = player.and_then(|player| {
+        let (player, transform, collider, direction) = player.into_inner();
+        let position = collider.absolute(transform).position();
+
+        sources
+            .iter()
+            .filter(|(entity, ..)| {
+                contacts
+                    .contacts_with(*entity)
+                    .any(|c| c.sensor && c.involves(player))
+            })
+            .map(|(entity, transform, trigger, _)| {
+                let offset = center(&trigger.0.absolute(transform)) - position;
+                let facing = direction.into_unit_vec2().dot(offset.normalize_or_zero());
+
+                (entity, offset.length() - facing * FACING_WEIGHT)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| entity)
+    });
+
+    for (entity, _, trigger, was_selected) in sources.iter() {
+        let is_selected = selected == Some(entity);
+
+        if is_selected && !was_selected {
+            commands
+                .entity(entity)
+                .insert(SelectedInteraction)
+                .with_child(interaction_prompt(&server, trigger));
+        } else if was_selected && !is_selected {
+            commands.entity(entity).remove::<SelectedInteraction>();
+            for (prompt, parent) in prompts.iter() {
+                if parent.get() == entity {
+                    commands.entity(prompt).despawn_recursive();
+                }
+            }
+        }
+    }
+}
+
+fn handle_interactions(
+    player: Option<Single<&ActionState<Action>, (With<Player>, Without<CutsceneMovement>)>>,
+    selected: Option<Single<&InteractionTriggerSource, With<SelectedInteraction>>>,
     mut writer: EventWriter<InteractionTrigger>,
 ) {
-    let Some((entity, action)) = player.map(|p| p.into_inner()) else {
+    let (Some(action), Some(source)) = (player, selected) else {
+        return;
+    };
+
+    if action.just_pressed(&Action::Interact) {
+        writer.send(InteractionTrigger(source.0));
+    }
+}
+
+/// A region that fires when the player walks in.
+#[derive(Component)]
+#[require(Transform)]
+struct TriggerVolumeSource {
+    volume: TriggerVolumes,
+    /// Only fire the first time the player walks in, remembered across scene loads and saves.
+    once: bool,
+    /// Only fire while the player faces the center of the region.
+    facing: bool,
+    /// Whether the volume fired since the player last walked in.
+    fired: bool,
+    /// Marks `once` volumes as spent in the [`CompletedFragments`].
+    key: String,
+}
+
+#[derive(Debug, Event)]
+struct TriggerVolumeEntered(TriggerVolumes);
+
+fn insert_trigger_volume_source(
+    mut commands: Commands,
+    entities: Query<
+        (
+            Entity,
+            &crate::annual::TriggerVolume,
+            &EntitySize,
+            &EntityIid,
+        ),
+        Added<crate::annual::TriggerVolume>,
+    >,
+) {
+    for (entity, volume, size, iid) in entities.iter() {
+        commands.entity(entity).insert((
+            TriggerVolumeSource {
+                volume: volume.volume,
+                once: volume.once,
+                facing: volume.facing,
+                fired: false,
+                key: format!("trigger_volume:{}", iid.0),
+            },
+            Trigger(instance_bounds(size, Vec2::ZERO)),
+            CollisionLayers::player_only(),
+        ));
+    }
+}
+
+fn handle_trigger_volumes(
+    player: Option<
+        Single<
+            (Entity, &GlobalTransform, &Collider, &Direction),
+            (With<Player>, Without<CutsceneMovement>),
+        >,
+    >,
+    mut volumes: Query<(Entity, &GlobalTransform, &Trigger, &mut TriggerVolumeSource)>,
+    contacts: Res<Contacts>,
+    mut completed: ResMut<CompletedFragments>,
+    mut writer: EventWriter<TriggerVolumeEntered>,
+) {
+    let Some((player, transform, collider, direction)) = player.map(|p| p.into_inner()) else {
         return;
     };
 
-    let interact = action.just_pressed(&Action::Interact);
-    for event in reader.read() {
-        if interact && event.target == entity {
-            if let Ok(int) = interactions.get(event.trigger) {
-                writer.send(InteractionTrigger(int.0));
+    let position = collider.absolute(transform).position();
+    for (entity, volume_transform, trigger, mut source) in volumes.iter_mut() {
+        let inside = contacts
+            .contacts_with(entity)
+            .any(|c| c.sensor && c.involves(player));
+
+        if !inside {
+            if source.fired {
+                source.fired = false;
+            }
+            continue;
+        }
+
+        if source.fired || (source.once && completed.0.contains(&source.key)) {
+            continue;
+        }
+
+        if source.facing {
+            let center = center(&trigger.0.absolute(volume_transform));
+            if direction.into_unit_vec2().dot(center - position) <= 0. {
+                continue;
             }
         }
+
+        source.fired = true;
+        if source.once {
+            completed.0.insert(source.key.clone());
+        }
+        writer.send(TriggerVolumeEntered(source.volume));
     }
 }
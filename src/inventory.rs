@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_sequence::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Tracks the key items Izzy carries.
+///
+/// Key items survive world resets.
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Inventory::default());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum KeyItem {
+    Pot,
+    SpringWater,
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct Inventory(pub HashSet<KeyItem>);
+
+impl Inventory {
+    pub fn contains(&self, item: KeyItem) -> bool {
+        self.0.contains(&item)
+    }
+}
+
+/// Whether Izzy carries `item`.
+pub fn has_item(item: KeyItem) -> impl Fn(Res<Inventory>) -> bool {
+    move |inventory: Res<Inventory>| inventory.contains(item)
+}
+
+pub trait InventoryFragment<D: Threaded, C: Threaded>: IntoFragment<D, C> + Sized {
+    /// Gives Izzy `item` once the fragment ends.
+    fn give_item(self, item: KeyItem) -> impl IntoFragment<D, C> {
+        self.on_end(move |mut inventory: ResMut<Inventory>| {
+            inventory.0.insert(item);
+        })
+    }
+}
+
+impl<D: Threaded, C: Threaded, T> InventoryFragment<D, C> for T where T: IntoFragment<D, C> {}
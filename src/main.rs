@@ -14,7 +14,8 @@ use bevy::{
 use bevy_seedling::{MainBus, VolumeNode};
 use characters::*;
 use cutscene::*;
-use scenes::SceneRoot;
+use save::SaveCommands;
+use scenes::SceneCommands;
 
 mod animation;
 mod annual;
@@ -23,10 +24,14 @@ mod characters;
 mod color;
 mod curves;
 mod cutscene;
+mod flags;
 mod frag_util;
 mod gfx;
 mod interactions;
+mod inventory;
 mod physics;
+mod reset;
+mod save;
 mod scenes;
 mod textbox;
 
@@ -73,6 +78,12 @@ fn main() {
             cutscene::CutscenePlugin,
             physics::PhysicsPlugin,
             interactions::InteractionPlugin,
+            (
+                save::SavePlugin,
+                flags::FlagsPlugin,
+                inventory::InventoryPlugin,
+                reset::WorldResetPlugin,
+            ),
             scenes::ScenePlugin,
             bevy_enoki::EnokiPlugin,
             bevy_seedling::SeedlingPlugin::default(),
@@ -98,7 +109,9 @@ fn startup(
 ) {
     global.into_inner().0.set(0.25);
 
-    commands.spawn(SceneRoot::new(scenes::park::ParkScene));
-    //commands.spawn(SceneRoot::new(scenes::home::BedroomScene::PotBreak));
-    // commands.spawn(SceneRoot::new(scenes::sandbox::SandboxScene));
+    // Jump straight to a scene with, e.g., `ANNUAL_SCENE=bedroom:PotBreak`.
+    match std::env::var("ANNUAL_SCENE") {
+        Ok(address) => commands.switch_scene(&address),
+        Err(_) => commands.load_game_or(scenes::park::ParkScene),
+    }
 }
@@ -1,32 +1,152 @@
+use super::contact::{Contact, Contacts};
+use super::debug::PhysicsStats;
+use super::hull::RoundedHull;
+use super::interpolation::{Position, PreviousPosition};
+use super::layers::CollisionLayers;
 use super::spatial;
 use crate::{annual, TILE_SIZE};
+use bevy::math::Affine3A;
 use bevy::prelude::*;
+use bevy::utils::hashbrown::{HashMap, HashSet};
+use bevy::utils::Instant;
 use spatial::{SpatialHash, StaticBodyData, StaticBodyStorage};
-use std::cmp::Ordering;
 
-/// Marks this entity as having a static position throughout the lifetime of the program.
+/// Marks this entity as rarely moving.
 ///
-/// All [`StaticBody`] entities are added to a [`spatial::SpatialHash`] after spawning.
+/// All [`StaticBody`] entities are added to their level's [`spatial::StaticBodyStorage`] after
+/// spawning, and removed when despawned.
 ///
-/// Moving a static body entity will NOT result in their collision being updated.
+/// Moving a static body entity, or changing its [`Collider`], rehashes its collision. This is
+/// fine for doors and pushable objects, but anything moving every frame should be a
+/// [`DynamicBody`].
 #[derive(Debug, Default, Clone, Copy, Component)]
-#[require(Collider)]
+#[require(Collider, CollisionLayers)]
+#[component(on_remove = spatial::remove_static_body_from_spatial_map)]
 pub struct StaticBody;
 
 #[derive(Debug, Default, Clone, Copy, Component)]
-#[require(Collider)]
+#[require(Collider, CollisionLayers, Position)]
 pub struct DynamicBody;
 
+/// Lets bodies pass through a [`StaticBody`] from every side but the one `normal` faces.
+///
+/// Only bodies that start a physics tick entirely on the `normal` side are blocked, so a body
+/// that is partway through carries on. Useful for ledges and hop down spots.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[require(StaticBody)]
+pub struct OneWay {
+    pub normal: Vec2,
+}
+
+/// How far a body may sink into a [`OneWay`] body and still be considered on its blocking side.
+const ONE_WAY_TOLERANCE: f32 = 0.05;
+
+impl OneWay {
+    pub fn new(normal: Vec2) -> Self {
+        Self {
+            normal: normal.normalize(),
+        }
+    }
+
+    /// Whether a body that started the tick as `body` is blocked by `collider`.
+    pub fn blocks(&self, collider: &AbsoluteCollider, body: &AbsoluteCollider) -> bool {
+        let (_, face) = collider.project(self.normal);
+        let (near, _) = body.project(self.normal);
+
+        near >= face - ONE_WAY_TOLERANCE
+    }
+}
+
+/// Bitmask of the sides a [`StaticBody`] pushes bodies out through.
+///
+/// Contacts that would push a body out through a disabled edge are ignored. Generated tile
+/// colliders disable edges shared with neighbouring tiles, so bodies sliding along a wall of
+/// tiles do not catch on the seams between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct CollisionEdges(pub u8);
+
+impl Default for CollisionEdges {
+    fn default() -> Self {
+        Self(Self::ALL)
+    }
+}
+
+#[allow(unused)]
+impl CollisionEdges {
+    pub const NONE: u8 = 0;
+    pub const TOP: u8 = 1;
+    pub const BOTTOM: u8 = 1 << 1;
+    pub const LEFT: u8 = 1 << 2;
+    pub const RIGHT: u8 = 1 << 3;
+    pub const ALL: u8 = Self::TOP | Self::BOTTOM | Self::LEFT | Self::RIGHT;
+
+    /// The edge a body pushed along `direction` leaves through.
+    pub fn edge(direction: Vec2) -> u8 {
+        if direction.x.abs() > direction.y.abs() {
+            if direction.x > 0. {
+                Self::RIGHT
+            } else {
+                Self::LEFT
+            }
+        } else if direction.y > 0. {
+            Self::TOP
+        } else {
+            Self::BOTTOM
+        }
+    }
+
+    /// Only the edge facing `normal`.
+    pub fn facing(normal: Vec2) -> Self {
+        Self(Self::edge(normal))
+    }
+
+    /// Whether a body may be pushed out along `direction`.
+    pub fn allows(&self, direction: Vec2) -> bool {
+        direction == Vec2::ZERO || self.0 & Self::edge(direction) != 0
+    }
+}
+
 /// Prevents a dynamic body entity from being pushed.
+///
+/// Treated as an infinite [`Mass`] when resolving collisions between dynamic bodies.
 #[derive(Debug, Default, Clone, Copy, Component)]
 pub struct Massive;
 
+/// Determines how far a [`DynamicBody`] is pushed relative to the other body in a collision.
+///
+/// Dynamic bodies without a [`Mass`] have a mass of `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct Mass(pub f32);
+
+impl Default for Mass {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// Configures the dynamic body collision solver.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct SolverSettings {
+    /// The number of times overlapping dynamic bodies are resolved each physics tick.
+    ///
+    /// More iterations settle stacks of bodies faster.
+    pub iterations: usize,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        Self { iterations: 4 }
+    }
+}
+
 /// To check for collisions, first convert this enum into an [`AbsoluteCollider`]
 /// with [`Collider::absolute`].
 #[derive(Debug, Clone, Copy, PartialEq, Component)]
 pub enum Collider {
     Rect(RectCollider),
     Circle(CircleCollider),
+    Capsule(CapsuleCollider),
+    Polygon(PolygonCollider),
 }
 
 impl Default for Collider {
@@ -44,32 +164,111 @@ impl Collider {
         Self::Circle(CircleCollider { position, radius })
     }
 
-    // TODO: make this work in bevy
-    pub fn absolute(&self, transform: &Transform) -> AbsoluteCollider {
+    pub fn from_capsule(start: Vec2, end: Vec2, radius: f32) -> Self {
+        Self::Capsule(CapsuleCollider { start, end, radius })
+    }
+
+    /// Builds a polygon from the convex hull of `vertices`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hull has more than [`MAX_POLYGON_VERTICES`] vertices.
+    pub fn from_polygon(vertices: &[Vec2]) -> Self {
+        Self::Polygon(PolygonCollider::new(vertices))
+    }
+
+    /// Places the collider in world space.
+    ///
+    /// Rects stay axis aligned unless rotated off axis, in which case they become polygons.
+    /// Circles and capsules are rounded by their largest scale axis.
+    pub fn absolute(&self, transform: &GlobalTransform) -> AbsoluteCollider {
+        let affine = transform.affine();
+        let point = |p: Vec2| affine.transform_point3(p.extend(0.)).xy();
+        let radius_scale = affine
+            .matrix3
+            .x_axis
+            .xy()
+            .length()
+            .max(affine.matrix3.y_axis.xy().length());
+
         match self {
-            Self::Rect(rect) => AbsoluteCollider::Rect(RectCollider {
-                tl: rect.tl + transform.translation.xy(),
-                size: rect.size,
-            }),
+            Self::Rect(rect) => {
+                let corners = [
+                    point(rect.tl),
+                    point(Vec2::new(rect.br().x, rect.tl.y)),
+                    point(rect.br()),
+                    point(Vec2::new(rect.tl.x, rect.br().y)),
+                ];
+
+                let (x_axis, y_axis) = (affine.matrix3.x_axis, affine.matrix3.y_axis);
+                let axis_aligned = (x_axis.y.abs() <= f32::EPSILON
+                    && y_axis.x.abs() <= f32::EPSILON)
+                    || (x_axis.x.abs() <= f32::EPSILON && y_axis.y.abs() <= f32::EPSILON);
+
+                if axis_aligned {
+                    let min = corners.into_iter().reduce(Vec2::min).unwrap();
+                    let max = corners.into_iter().reduce(Vec2::max).unwrap();
+                    AbsoluteCollider::Rect(RectCollider {
+                        tl: min,
+                        size: max - min,
+                    })
+                } else {
+                    AbsoluteCollider::Polygon(PolygonCollider::new(&corners))
+                }
+            }
             Self::Circle(circle) => AbsoluteCollider::Circle(CircleCollider {
-                position: circle.position + transform.translation.xy(),
-                radius: circle.radius,
+                position: point(circle.position),
+                radius: circle.radius * radius_scale,
+            }),
+            Self::Capsule(capsule) => AbsoluteCollider::Capsule(CapsuleCollider {
+                start: point(capsule.start),
+                end: point(capsule.end),
+                radius: capsule.radius * radius_scale,
             }),
+            Self::Polygon(polygon) => AbsoluteCollider::Polygon(polygon.transformed(point)),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum AbsoluteCollider {
     Rect(RectCollider),
     Circle(CircleCollider),
+    Capsule(CapsuleCollider),
+    Polygon(PolygonCollider),
 }
 
 impl AbsoluteCollider {
+    /// The extent of the collider along `axis`.
+    pub fn project(&self, axis: Vec2) -> (f32, f32) {
+        RoundedHull::from(self).project(axis)
+    }
+
+    pub fn translated(&self, offset: Vec2) -> Self {
+        match *self {
+            Self::Rect(rect) => Self::Rect(RectCollider {
+                tl: rect.tl + offset,
+                size: rect.size,
+            }),
+            Self::Circle(circle) => Self::Circle(CircleCollider {
+                position: circle.position + offset,
+                radius: circle.radius,
+            }),
+            Self::Capsule(capsule) => Self::Capsule(CapsuleCollider {
+                start: capsule.start + offset,
+                end: capsule.end + offset,
+                radius: capsule.radius,
+            }),
+            Self::Polygon(polygon) => Self::Polygon(polygon.translated(offset)),
+        }
+    }
+
     pub fn position(&self) -> Vec2 {
         match self {
             Self::Rect(rect) => rect.tl,
             Self::Circle(circle) => circle.position,
+            Self::Capsule(capsule) => (capsule.start + capsule.end) / 2.,
+            Self::Polygon(polygon) => polygon.center(),
         }
     }
 
@@ -77,6 +276,8 @@ impl AbsoluteCollider {
         match self {
             Self::Rect(rect) => rect.tl.x + rect.size.x,
             Self::Circle(circle) => circle.position.x + circle.radius,
+            Self::Capsule(capsule) => capsule.start.x.max(capsule.end.x) + capsule.radius,
+            Self::Polygon(polygon) => polygon.max().x,
         }
     }
 
@@ -84,6 +285,8 @@ impl AbsoluteCollider {
         match self {
             Self::Rect(rect) => rect.tl.x,
             Self::Circle(circle) => circle.position.x - circle.radius,
+            Self::Capsule(capsule) => capsule.start.x.min(capsule.end.x) - capsule.radius,
+            Self::Polygon(polygon) => polygon.min().x,
         }
     }
 
@@ -91,6 +294,8 @@ impl AbsoluteCollider {
         match self {
             Self::Rect(rect) => rect.tl.y + rect.size.y,
             Self::Circle(circle) => circle.position.y + circle.radius,
+            Self::Capsule(capsule) => capsule.start.y.max(capsule.end.y) + capsule.radius,
+            Self::Polygon(polygon) => polygon.max().y,
         }
     }
 
@@ -98,6 +303,8 @@ impl AbsoluteCollider {
         match self {
             Self::Rect(rect) => rect.tl.y,
             Self::Circle(circle) => circle.position.y - circle.radius,
+            Self::Capsule(capsule) => capsule.start.y.min(capsule.end.y) - capsule.radius,
+            Self::Polygon(polygon) => polygon.min().y,
         }
     }
 }
@@ -109,6 +316,7 @@ impl CollidesWith<Self> for AbsoluteCollider {
             (Self::Rect(s), Self::Circle(o)) => s.collides_with(o),
             (Self::Circle(s), Self::Rect(o)) => s.collides_with(o),
             (Self::Circle(s), Self::Circle(o)) => s.collides_with(o),
+            (s, o) => RoundedHull::from(s).collides_with(&RoundedHull::from(o)),
         }
     }
 
@@ -118,6 +326,7 @@ impl CollidesWith<Self> for AbsoluteCollider {
             (Self::Rect(s), Self::Circle(o)) => s.resolution(o),
             (Self::Circle(s), Self::Rect(o)) => s.resolution(o),
             (Self::Circle(s), Self::Circle(o)) => s.resolution(o),
+            (s, o) => RoundedHull::from(s).resolution(&RoundedHull::from(o)),
         }
     }
 }
@@ -300,255 +509,487 @@ impl CollidesWith<CircleCollider> for RectCollider {
     }
 
     fn resolution(&self, other: &CircleCollider) -> Vec2 {
-        other.resolution(self)
+        -other.resolution(self)
+    }
+}
+
+/// A line segment from `start` to `end`, grown by `radius`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+pub struct CapsuleCollider {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub radius: f32,
+}
+
+/// The maximum number of vertices in a [`PolygonCollider`].
+pub const MAX_POLYGON_VERTICES: usize = 8;
+
+/// A convex polygon, wound counter-clockwise.
+///
+/// Vertices are stored inline so colliders stay [`Copy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+pub struct PolygonCollider {
+    vertices: [Vec2; MAX_POLYGON_VERTICES],
+    len: usize,
+}
+
+impl PolygonCollider {
+    /// Builds a polygon from the convex hull of `vertices`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hull has more than [`MAX_POLYGON_VERTICES`] vertices.
+    pub fn new(vertices: &[Vec2]) -> Self {
+        let mut points = vertices.to_vec();
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        points.dedup();
+
+        // Andrew's monotone chain, building the lower then upper hull.
+        let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() * 2);
+        for pass in 0..2 {
+            if points.len() < 2 {
+                hull.extend_from_slice(&points);
+                break;
+            }
+            let start = hull.len();
+            for &point in points.iter() {
+                while hull.len() >= start + 2
+                    && (hull[hull.len() - 1] - hull[hull.len() - 2])
+                        .perp_dot(point - hull[hull.len() - 2])
+                        <= 0.
+                {
+                    hull.pop();
+                }
+                hull.push(point);
+            }
+            hull.pop();
+
+            if pass == 0 {
+                points.reverse();
+            }
+        }
+
+        assert!(
+            hull.len() <= MAX_POLYGON_VERTICES,
+            "polygon collider has {} vertices, the maximum is {MAX_POLYGON_VERTICES}",
+            hull.len()
+        );
+
+        let mut polygon = Self {
+            len: hull.len(),
+            ..Default::default()
+        };
+        polygon.vertices[..hull.len()].copy_from_slice(&hull);
+
+        polygon
+    }
+
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices[..self.len]
+    }
+
+    pub fn translated(&self, offset: Vec2) -> Self {
+        self.transformed(|v| v + offset)
+    }
+
+    /// Maps every vertex through `f`, which must preserve convexity.
+    pub fn transformed(&self, f: impl Fn(Vec2) -> Vec2) -> Self {
+        let mut polygon = *self;
+        polygon.vertices[..polygon.len]
+            .iter_mut()
+            .for_each(|v| *v = f(*v));
+
+        // Mirroring flips the winding.
+        let vertices = polygon.vertices();
+        let signed_area = (0..vertices.len())
+            .map(|i| vertices[i].perp_dot(vertices[(i + 1) % vertices.len()]))
+            .sum::<f32>();
+        if signed_area < 0. {
+            polygon.vertices[..polygon.len].reverse();
+        }
+
+        polygon
+    }
+
+    /// The average of the polygon's vertices.
+    pub fn center(&self) -> Vec2 {
+        self.vertices().iter().sum::<Vec2>() / self.len.max(1) as f32
+    }
+
+    pub fn min(&self) -> Vec2 {
+        self.vertices()
+            .iter()
+            .copied()
+            .reduce(Vec2::min)
+            .unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Vec2 {
+        self.vertices()
+            .iter()
+            .copied()
+            .reduce(Vec2::max)
+            .unwrap_or_default()
     }
 }
 
 pub fn handle_collisions(
-    static_body_storage: Single<&SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
-    mut dynamic_bodies: Query<(&mut Transform, &Collider), With<DynamicBody>>,
+    static_body_storage: Query<&SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    mut dynamic_bodies: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut GlobalTransform,
+            &Collider,
+            &CollisionLayers,
+            &PreviousPosition,
+        ),
+        With<DynamicBody>,
+    >,
+    mut contacts: ResMut<Contacts>,
+    mut stats: ResMut<PhysicsStats>,
 ) {
-    let map = static_body_storage.into_inner();
+    let start = Instant::now();
+    let mut pairs_tested = 0;
+
+    for (entity, mut transform, mut global, collider, layers, previous) in dynamic_bodies.iter_mut()
+    {
+        let mut collider = collider.absolute(&global);
+        let mut offset = Vec2::ZERO;
+
+        // Where the body started the tick, for one-way bodies. Positions are in the parent's
+        // space.
+        let start = parent_affine(&transform, &global)
+            .transform_point3(previous.0.extend(transform.translation.z));
+        let previous = collider.translated(start.xy() - global.translation().xy());
+
+        for map in static_body_storage.iter() {
+            for spatial::SpatialData {
+                entity: se,
+                collider: sc,
+                data: sd,
+            } in map.nearby_objects(&collider.position())
+            {
+                if !layers.interacts_with(&sd.layers) {
+                    continue;
+                }
 
-    for (mut transform, collider) in dynamic_bodies.iter_mut() {
-        let original_collider = &collider;
-        let mut collider = collider.absolute(&transform);
+                pairs_tested += 1;
+                if collider.collides_with(sc) {
+                    let res_v = collider.resolution(sc);
+                    if !sd.blocks(sc, &previous, res_v) {
+                        continue;
+                    }
 
-        for spatial::SpatialData { collider: sc, .. } in map.nearby_objects(&collider.position()) {
-            if collider.collides_with(sc) {
-                let res_v = collider.resolution(sc);
-                transform.translation += Vec3::new(res_v.x, res_v.y, 0.);
-                collider = original_collider.absolute(&transform);
+                    contacts.insert(Contact::from_resolution(entity, *se, res_v, false));
+                    offset += res_v;
+                    collider = collider.translated(res_v);
+                }
             }
         }
+
+        if offset != Vec2::ZERO {
+            translate_global(&mut transform, &mut global, offset);
+        }
     }
+
+    stats.pairs_tested = pairs_tested;
+    stats.collision_time = start.elapsed();
 }
 
 pub fn handle_dynamic_body_collsions(
     mut dynamic_bodies: Query<
-        (Entity, &mut Transform, &Collider, Option<&Massive>),
+        (
+            Entity,
+            &mut Transform,
+            &mut GlobalTransform,
+            &Collider,
+            &CollisionLayers,
+            Option<&Mass>,
+            Option<&Massive>,
+        ),
         With<DynamicBody>,
     >,
+    settings: Res<SolverSettings>,
+    mut contacts: ResMut<Contacts>,
 ) {
+    // Sort by entity so the result does not depend on query order.
     let mut dynamic_bodies = dynamic_bodies.iter_mut().collect::<Vec<_>>();
-    dynamic_bodies.sort_by_key(|(_, _, _, m)| {
-        if m.is_some() {
-            Ordering::Greater
-        } else {
-            Ordering::Less
-        }
-    });
+    dynamic_bodies.sort_by_key(|(entity, ..)| *entity);
 
-    let mut spatial = spatial::SpatialHash::new(32.);
+    let origins = dynamic_bodies
+        .iter()
+        .map(|(_, _, global, collider, ..)| collider.absolute(global))
+        .collect::<Vec<_>>();
+    let mut offsets = vec![Vec2::ZERO; dynamic_bodies.len()];
+    let inverse_masses = dynamic_bodies
+        .iter()
+        .map(|(_, _, _, _, _, mass, massive)| match massive {
+            Some(_) => 0.,
+            None => 1. / mass.map(|m| m.0).unwrap_or(1.).max(f32::EPSILON),
+        })
+        .collect::<Vec<_>>();
+
+    for _ in 0..settings.iterations {
+        let colliders = origins
+            .iter()
+            .zip(offsets.iter())
+            .map(|(origin, offset)| origin.translated(*offset))
+            .collect::<Vec<_>>();
+
+        let spatial = spatial::SpatialHash::new_with(
+            32.,
+            dynamic_bodies.iter().zip(colliders.iter()).enumerate().map(
+                |(i, ((entity, ..), collider))| spatial::SpatialData {
+                    entity: *entity,
+                    collider: *collider,
+                    data: i,
+                },
+            ),
+        );
 
-    for (entity, transform, collider, massive) in dynamic_bodies.iter() {
-        let absolute = collider.absolute(transform);
-        spatial.insert(spatial::SpatialData {
-            collider: absolute,
-            data: (massive.cloned(), *collider),
-            entity: *entity,
-        });
-    }
+        let mut pairs = Vec::new();
+        for (i, collider) in colliders.iter().enumerate() {
+            let min = Vec2::new(collider.min_x(), collider.min_y());
+            let max = Vec2::new(collider.max_x(), collider.max_y());
 
-    for (entity, transform, collider, massive) in dynamic_bodies.iter_mut() {
-        let original_collider = &collider;
-        let mut collider = collider.absolute(transform);
-
-        let mut update_active = false;
-
-        // TODO: this shit is awful
-        //
-        // For some reason god forsaken, this will update twice even though the position in the hash is updated
-        // before the other, overlapping entity updates itself.
-        for spatial::SpatialData {
-            entity: se,
-            collider: sc,
-            data: d,
-            ..
-        } in spatial.nearby_objects(&collider.position())
-        {
-            if *entity != *se && collider.collides_with(sc) && massive.is_none() {
-                let res_v = collider.resolution(sc);
-                transform.translation += Vec3::new(res_v.x, res_v.y, 0.);
-                collider = original_collider.absolute(transform);
-                update_active = true;
-
-                if d.0.is_some() && massive.is_some() {
-                    warn!("resolving collision between two massive bodies");
+            for spatial::SpatialData { data: j, .. } in spatial.objects_in_rect(&min, &max) {
+                if i < *j && dynamic_bodies[i].4.interacts_with(dynamic_bodies[*j].4) {
+                    pairs.push((i, *j));
                 }
             }
         }
+        pairs.sort_unstable();
+        pairs.dedup();
 
-        if update_active {
-            for spatial::SpatialData {
-                entity: se,
-                collider: sc,
-                ..
-            } in spatial.objects_in_cell_mut(&collider.position())
-            {
-                if *se == *entity {
-                    *sc = collider;
-                    break;
-                }
+        if pairs.is_empty() {
+            break;
+        }
+
+        for (i, j) in pairs.into_iter() {
+            let a = origins[i].translated(offsets[i]);
+            let b = origins[j].translated(offsets[j]);
+
+            if !a.collides_with(&b) {
+                continue;
             }
+
+            // Pairs may first touch in a later iteration. Only the first contact of a pair is
+            // kept, with its deepest penetration.
+            let res_v = a.resolution(&b);
+            contacts.insert(Contact::from_resolution(
+                dynamic_bodies[i].0,
+                dynamic_bodies[j].0,
+                res_v,
+                false,
+            ));
+
+            let total = inverse_masses[i] + inverse_masses[j];
+            if total == 0. {
+                warn_once!("could not resolve collision between two massive bodies");
+                continue;
+            }
+
+            // Split the resolution by relative mass, the lighter body moving further.
+            offsets[i] += res_v * inverse_masses[i] / total;
+            offsets[j] -= res_v * inverse_masses[j] / total;
+        }
+    }
+
+    for ((_, transform, global, ..), offset) in dynamic_bodies.iter_mut().zip(offsets) {
+        if offset != Vec2::ZERO {
+            translate_global(transform, global, offset);
         }
     }
 }
 
-// TODO: collider collapsing vertically
+/// Moves an entity by `offset` in world space.
+///
+/// The [`GlobalTransform`] is updated alongside the [`Transform`] so that later physics systems
+/// see the new position before transforms are propagated again.
+/// The world space transform of a body's parent, recovered from the body's transforms.
+fn parent_affine(transform: &Transform, global: &GlobalTransform) -> Affine3A {
+    global.affine() * transform.compute_affine().inverse()
+}
+
+pub(super) fn translate_global(
+    transform: &mut Transform,
+    global: &mut GlobalTransform,
+    offset: Vec2,
+) {
+    let parent = parent_affine(transform, global);
+    transform.translation += parent.inverse().transform_vector3(offset.extend(0.));
+
+    let mut affine = global.affine();
+    affine.translation.x += offset.x;
+    affine.translation.y += offset.y;
+    *global = affine.into();
+}
+
+/// Marks a cell of the `OneWay` IntGrid layer as part of a [`OneWay`] body facing the given grid
+/// direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct OneWayTile(pub IVec2);
+
 pub fn build_tile_set_colliders(
     mut commands: Commands,
     tiles: Query<(&Transform, &Parent), Added<annual::TileSolid>>,
-    levels: Query<Entity>,
-    manual_collision: Query<&Transform, Added<annual::Collision>>,
+    manual_collision: Query<(&Transform, &Parent), Added<annual::Collision>>,
+    one_way_tiles: Query<(&Transform, &Parent, &OneWayTile), Added<OneWayTile>>,
 ) {
-    //let mut num_colliders = 0;
-
-    // ~14k without combining
-    // ~600 with horizontal combining
-
-    if tiles.is_empty() {
+    if tiles.is_empty() && manual_collision.is_empty() && one_way_tiles.is_empty() {
         return;
     }
 
-    // WARN: assumes that one level is loaded at a time!!
-    let level = tiles
-        .iter()
-        .next()
-        .map(|(_, p)| levels.get(p.get()).unwrap())
-        .unwrap();
-
-    let mut cached_collider_positions = Vec::with_capacity(1024);
     let tile_size = TILE_SIZE;
-
     let offset = tile_size / 2.;
-    for transform in tiles.iter().map(|(t, _)| t).chain(manual_collision.iter()) {
-        cached_collider_positions.push(Vec2::new(
-            transform.translation.x + offset,
-            transform.translation.y + offset,
-        ));
-    }
 
-    if cached_collider_positions.is_empty() {
-        return;
+    let mut level_collider_positions = HashMap::<Entity, Vec<Vec2>>::default();
+    for (transform, level) in tiles.iter().chain(manual_collision.iter()) {
+        level_collider_positions
+            .entry(level.get())
+            .or_insert_with(|| Vec::with_capacity(1024))
+            .push(Vec2::new(
+                transform.translation.x + offset,
+                transform.translation.y + offset,
+            ));
     }
 
-    commands.entity(level).with_children(|level| {
-        for (pos, collider) in
-            build_colliders_from_vec2(cached_collider_positions, tile_size).into_iter()
-        {
-            level.spawn((
-                Transform::from_translation((pos - Vec2::splat(tile_size / 2.)).extend(0.)),
-                StaticBody,
-                collider,
+    let mut level_one_way_positions = HashMap::<(Entity, IVec2), Vec<Vec2>>::default();
+    for (transform, level, tile) in one_way_tiles.iter() {
+        level_one_way_positions
+            .entry((level.get(), tile.0))
+            .or_default()
+            .push(Vec2::new(
+                transform.translation.x + offset,
+                transform.translation.y + offset,
             ));
-            //num_colliders += 1;
-        }
-    });
+    }
 
-    //println!("num_colliders: {num_colliders}");
-}
+    for (level, cached_collider_positions) in level_collider_positions.into_iter() {
+        let occupied = cached_collider_positions
+            .iter()
+            .map(|p| (*p / tile_size).floor().as_ivec2())
+            .collect::<HashSet<_>>();
 
-fn build_colliders_from_vec2(mut positions: Vec<Vec2>, tile_size: f32) -> Vec<(Vec2, Collider)> {
-    positions.sort_by(|a, b| {
-        let y_cmp = a.y.partial_cmp(&b.y).unwrap_or(std::cmp::Ordering::Equal);
-        if y_cmp == std::cmp::Ordering::Equal {
-            a.x.partial_cmp(&b.x).unwrap_or(std::cmp::Ordering::Equal)
-        } else {
-            y_cmp
-        }
-    });
-
-    let mut rows = Vec::with_capacity(positions.len() / 2);
-    let mut current_y = None;
-    let mut current_xs = Vec::with_capacity(positions.len() / 2);
-    for v in positions.into_iter() {
-        match current_y {
-            None => {
-                current_y = Some(v.y);
-                current_xs.push(v.x);
+        commands.entity(level).with_children(|level| {
+            for (pos, collider) in
+                build_colliders_from_vec2(cached_collider_positions, tile_size).into_iter()
+            {
+                level.spawn((
+                    Transform::from_translation((pos - Vec2::splat(tile_size / 2.)).extend(0.)),
+                    StaticBody,
+                    exposed_edges(pos, &collider, &occupied, tile_size),
+                    collider,
+                ));
             }
-            Some(y) => {
-                if v.y == y {
-                    current_xs.push(v.x);
-                } else {
-                    rows.push((y, current_xs.clone()));
-                    current_xs.clear();
+        });
+    }
 
-                    current_y = Some(v.y);
-                    current_xs.push(v.x);
-                }
+    for ((level, normal), positions) in level_one_way_positions.into_iter() {
+        let normal = normal.as_vec2();
+
+        commands.entity(level).with_children(|level| {
+            for (pos, collider) in build_colliders_from_vec2(positions, tile_size).into_iter() {
+                level.spawn((
+                    Transform::from_translation((pos - Vec2::splat(tile_size / 2.)).extend(0.)),
+                    OneWay::new(normal),
+                    CollisionEdges::facing(normal),
+                    collider,
+                ));
             }
-        }
+        });
     }
+}
 
-    match current_y {
-        Some(y) => {
-            rows.push((y, current_xs));
-        }
-        None => unreachable!(),
+/// Disables the edges of a block from [`build_colliders_from_vec2`] that are entirely covered
+/// by `occupied` tiles.
+fn exposed_edges(
+    pos: Vec2,
+    collider: &Collider,
+    occupied: &HashSet<IVec2>,
+    tile_size: f32,
+) -> CollisionEdges {
+    let Collider::Rect(rect) = collider else {
+        return CollisionEdges::default();
+    };
+
+    let min = (Vec2::new(pos.x, pos.y + tile_size) / tile_size)
+        .floor()
+        .as_ivec2();
+    let max = min + (rect.size / tile_size).round().as_ivec2() - IVec2::ONE;
+
+    let row = |y: i32| (min.x..=max.x).all(|x| occupied.contains(&IVec2::new(x, y)));
+    let column = |x: i32| (min.y..=max.y).all(|y| occupied.contains(&IVec2::new(x, y)));
+
+    let mut edges = CollisionEdges::ALL;
+    if row(max.y + 1) {
+        edges &= !CollisionEdges::TOP;
     }
-
-    #[derive(Debug, Clone, Copy)]
-    struct Plate {
-        y: f32,
-        x_start: f32,
-        x_end: f32,
+    if row(min.y - 1) {
+        edges &= !CollisionEdges::BOTTOM;
+    }
+    if column(min.x - 1) {
+        edges &= !CollisionEdges::LEFT;
+    }
+    if column(max.x + 1) {
+        edges &= !CollisionEdges::RIGHT;
     }
 
-    let mut row_plates = Vec::with_capacity(rows.len());
-    for (y, row) in rows.into_iter() {
-        let mut current_x = None;
-        let mut x_start = None;
-        let mut plates = Vec::with_capacity(row.len() / 4);
+    CollisionEdges(edges)
+}
 
-        for x in row.iter() {
-            match (current_x, x_start) {
-                (None, None) => {
-                    current_x = Some(*x);
-                    x_start = Some(*x);
-                }
-                (Some(cx), Some(xs)) => {
-                    if *x > cx + tile_size {
-                        plates.push(Plate {
-                            x_end: cx + tile_size,
-                            x_start: xs,
-                            y,
-                        });
-                        x_start = Some(*x);
-                    }
+/// Merges tiles at `positions` into as few rectangles as it greedily can.
+///
+/// Starting from the lowest row, each uncovered tile grows right as far as the row allows, then
+/// up for as long as every tile above the span is uncovered. The rectangles never overlap and
+/// cover exactly the input tiles.
+pub(super) fn build_colliders_from_vec2(
+    positions: Vec<Vec2>,
+    tile_size: f32,
+) -> Vec<(Vec2, Collider)> {
+    let Some(origin) = positions.first().copied() else {
+        return Vec::new();
+    };
+
+    let tiles = positions
+        .iter()
+        .map(|p| ((*p - origin) / tile_size).round().as_ivec2())
+        .collect::<HashSet<_>>();
 
-                    current_x = Some(*x);
-                }
-                _ => unreachable!(),
-            }
+    let mut sorted = tiles.iter().copied().collect::<Vec<_>>();
+    sorted.sort_unstable_by_key(|tile| (tile.y, tile.x));
+
+    let mut covered = HashSet::<IVec2>::with_capacity(tiles.len());
+    let mut output = Vec::new();
+    for start in sorted.into_iter() {
+        if covered.contains(&start) {
+            continue;
         }
 
-        match (current_x, x_start) {
-            (Some(cx), Some(xs)) => {
-                plates.push(Plate {
-                    x_end: cx + tile_size,
-                    x_start: xs,
-                    y,
-                });
-            }
-            _ => unreachable!(),
+        let free = |tile: IVec2| tiles.contains(&tile) && !covered.contains(&tile);
+
+        let mut width = 1;
+        while free(start + IVec2::new(width, 0)) {
+            width += 1;
         }
 
-        row_plates.push(plates);
-    }
+        let mut height = 1;
+        while (0..width).all(|x| free(start + IVec2::new(x, height))) {
+            height += 1;
+        }
 
-    let mut output = Vec::new();
-    for plates in row_plates.into_iter() {
-        for plate in plates.into_iter() {
-            output.push((
-                Vec2::new(plate.x_start, plate.y - tile_size),
-                Collider::from_rect(
-                    Vec2::ZERO,
-                    Vec2::new(plate.x_end - plate.x_start, tile_size),
-                ),
-            ));
+        for y in 0..height {
+            for x in 0..width {
+                covered.insert(start + IVec2::new(x, y));
+            }
         }
+
+        let position = origin + start.as_vec2() * tile_size;
+        output.push((
+            Vec2::new(position.x, position.y - tile_size),
+            Collider::from_rect(Vec2::ZERO, IVec2::new(width, height).as_vec2() * tile_size),
+        ));
     }
 
     output
@@ -0,0 +1,119 @@
+use bevy::{prelude::*, utils::hashbrown::HashMap};
+
+/// A contact between two colliders, found during the last physics tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// The body that was resolved, or the [`Trigger`](super::trigger::Trigger) that fired.
+    pub entity: Entity,
+    pub other: Entity,
+    /// Points from `other` towards `entity`.
+    pub normal: Vec2,
+    /// Depth of the overlap along `normal` before resolution.
+    pub penetration: f32,
+    /// Whether this contact is with a [`Trigger`](super::trigger::Trigger).
+    pub sensor: bool,
+}
+
+impl Contact {
+    pub fn from_resolution(entity: Entity, other: Entity, resolution: Vec2, sensor: bool) -> Self {
+        Self {
+            entity,
+            other,
+            normal: resolution.normalize_or_zero(),
+            penetration: resolution.length(),
+            sensor,
+        }
+    }
+
+    pub fn involves(&self, entity: Entity) -> bool {
+        self.entity == entity || self.other == entity
+    }
+
+    fn key(&self) -> ContactKey {
+        ContactKey {
+            a: self.entity.min(self.other),
+            b: self.entity.max(self.other),
+            sensor: self.sensor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ContactKey {
+    a: Entity,
+    b: Entity,
+    sensor: bool,
+}
+
+/// Sent the first tick two colliders touch.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct CollisionStarted(pub Contact);
+
+/// Sent every following tick that two colliders remain in contact.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct CollisionOngoing(pub Contact);
+
+/// Sent the first tick two colliders stop touching, with their last contact.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct CollisionEnded(pub Contact);
+
+/// Every pair of colliders in contact.
+///
+/// Each pair is reported once per tick, regardless of which side found the contact.
+#[derive(Debug, Default, Resource)]
+pub struct Contacts {
+    active: HashMap<ContactKey, Contact>,
+    pending: HashMap<ContactKey, Contact>,
+}
+
+#[allow(dead_code)]
+impl Contacts {
+    /// Records a contact for the current tick.
+    pub fn insert(&mut self, contact: Contact) {
+        self.pending.entry(contact.key()).or_insert(contact);
+    }
+
+    /// Contacts found during the last physics tick.
+    pub fn iter(&self) -> impl Iterator<Item = &Contact> {
+        self.active.values()
+    }
+
+    /// The solid contact between two entities during the last physics tick.
+    pub fn get(&self, a: Entity, b: Entity) -> Option<&Contact> {
+        self.active.get(&ContactKey {
+            a: a.min(b),
+            b: a.max(b),
+            sensor: false,
+        })
+    }
+
+    pub fn contacts_with(&self, entity: Entity) -> impl Iterator<Item = &Contact> {
+        self.iter().filter(move |c| c.involves(entity))
+    }
+}
+
+pub fn emit_collision_events(
+    mut contacts: ResMut<Contacts>,
+    mut started: EventWriter<CollisionStarted>,
+    mut ongoing: EventWriter<CollisionOngoing>,
+    mut ended: EventWriter<CollisionEnded>,
+) {
+    let contacts = &mut *contacts;
+
+    for (key, contact) in contacts.pending.iter() {
+        if contacts.active.contains_key(key) {
+            ongoing.send(CollisionOngoing(*contact));
+        } else {
+            started.send(CollisionStarted(*contact));
+        }
+    }
+
+    for (key, contact) in contacts.active.iter() {
+        if !contacts.pending.contains_key(key) {
+            ended.send(CollisionEnded(*contact));
+        }
+    }
+
+    std::mem::swap(&mut contacts.active, &mut contacts.pending);
+    contacts.pending.clear();
+}
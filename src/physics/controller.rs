@@ -0,0 +1,97 @@
+use super::collision::{translate_global, Collider, DynamicBody};
+use super::contact::{Contact, Contacts};
+use super::layers::CollisionLayers;
+use super::spatial::{SpatialHash, StaticBodyData, StaticBodyStorage};
+use super::sweep::collide_and_slide;
+use super::velocity::Velocity;
+use bevy::prelude::*;
+
+/// Moves a [`DynamicBody`] by its [`Velocity`], sliding along static bodies instead of
+/// walking into them and being pushed back out.
+///
+/// Write the desired [`Velocity`] and let the physics schedule do the rest. Movement is
+/// resolved against static bodies only, other dynamic bodies still push each other apart.
+#[derive(Debug, Default, Clone, Copy, Component)]
+#[require(DynamicBody, Velocity)]
+pub struct KinematicController {
+    grounded: bool,
+    blocked: bool,
+}
+
+/// Surfaces whose normal is within this many radians of up are considered ground.
+const MAX_GROUND_ANGLE: f32 = std::f32::consts::FRAC_PI_4;
+
+impl KinematicController {
+    /// Whether the controller touched a surface facing up during the last physics tick.
+    pub fn grounded(&self) -> bool {
+        self.grounded
+    }
+
+    /// Whether any of the controller's motion was stopped by a surface during the last
+    /// physics tick.
+    pub fn blocked(&self) -> bool {
+        self.blocked
+    }
+}
+
+pub fn move_kinematic_controllers(
+    static_body_storage: Query<&SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    mut controllers: Query<(
+        Entity,
+        &mut Transform,
+        &mut GlobalTransform,
+        &mut KinematicController,
+        &mut Velocity,
+        &Collider,
+        &CollisionLayers,
+    )>,
+    mut contacts: ResMut<Contacts>,
+    time: Res<Time<Fixed>>,
+) {
+    for (entity, mut transform, mut global, mut controller, mut velocity, collider, layers) in
+        controllers.iter_mut()
+    {
+        let mut grounded = false;
+        let mut blocked = false;
+        let mut slid = velocity.0;
+
+        let offset = collide_and_slide(
+            &static_body_storage,
+            &collider.absolute(&global),
+            layers,
+            velocity.0 * time.delta_secs(),
+            |other, hit| {
+                grounded |= hit.normal.angle_to(Vec2::Y).abs() <= MAX_GROUND_ANGLE;
+                blocked = true;
+
+                // Stop pushing into the surface, so the next tick starts out sliding.
+                let into = slid.dot(hit.normal);
+                if into < 0. {
+                    slid -= hit.normal * into;
+                }
+
+                contacts.insert(Contact {
+                    entity,
+                    other,
+                    normal: hit.normal,
+                    penetration: 0.,
+                    sensor: false,
+                });
+            },
+        );
+
+        if offset != Vec2::ZERO {
+            translate_global(&mut transform, &mut global, offset);
+        }
+
+        if slid != velocity.0 {
+            velocity.0 = slid;
+        }
+
+        // Avoid triggering change detection every tick.
+        if controller.grounded != grounded || controller.blocked != blocked {
+            controller.grounded = grounded;
+            controller.blocked = blocked;
+        }
+    }
+}
@@ -1,14 +1,48 @@
 use super::collision::{Collider, CollidesWith, DynamicBody, StaticBody};
+use super::contact::Contacts;
+use super::layers::CollisionLayers;
+use super::spatial::{SpatialHash, StaticBodyData, StaticBodyStorage};
 use super::trigger::{Trigger, TriggerEvent};
 use crate::gfx::pixel_perfect::HIGH_RES_LAYER;
 use bevy::input::keyboard::KeyboardInput;
 use bevy::input::ButtonState;
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
 use bevy::sprite::{Wireframe2d, Wireframe2dColor};
+use std::time::Duration;
 
 #[derive(Resource)]
 pub struct ShowCollision(pub bool);
 
+/// Measurements from the last physics tick, shown in the collision overlay.
+#[derive(Debug, Default, Clone, Copy, Resource)]
+pub struct PhysicsStats {
+    /// Pairs of dynamic and static bodies tested for overlap.
+    pub pairs_tested: usize,
+    /// Time spent resolving dynamic bodies against static bodies.
+    pub collision_time: Duration,
+}
+
+/// Gizmos drawn by the collision overlay.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct PhysicsGizmos;
+
+impl PhysicsGizmos {
+    pub fn config() -> GizmoConfig {
+        GizmoConfig {
+            render_layers: HIGH_RES_LAYER,
+            ..Default::default()
+        }
+    }
+}
+
+/// Spatial hash cells with this many colliders are drawn fully red.
+const CROWDED_CELL: usize = 8;
+
+/// Length of the contact normals drawn on top of their resolution.
+const NORMAL_LENGTH: f32 = 8.;
+
 impl Collider {
     fn debug_wireframe_bundle(
         &self,
@@ -34,6 +68,27 @@ impl Collider {
                     MeshMaterial2d(materials.add(Color::NONE)),
                     Transform::from_xyz(circle.position.x, circle.position.y, 999.),
                 ),
+                Self::Capsule(capsule) => {
+                    let axis = capsule.end - capsule.start;
+                    let center = (capsule.start + capsule.end) / 2.;
+
+                    (
+                        Mesh2d(meshes.add(Capsule2d::new(capsule.radius, axis.length()))),
+                        MeshMaterial2d(materials.add(Color::NONE)),
+                        Transform::from_xyz(center.x, center.y, 999.).with_rotation(
+                            Quat::from_rotation_z(
+                                axis.try_normalize()
+                                    .map(|axis| Vec2::Y.angle_to(axis))
+                                    .unwrap_or_default(),
+                            ),
+                        ),
+                    )
+                }
+                Self::Polygon(polygon) => (
+                    Mesh2d(meshes.add(polygon_mesh(polygon.vertices()))),
+                    MeshMaterial2d(materials.add(Color::NONE)),
+                    Transform::from_xyz(0., 0., 999.),
+                ),
             },
             HIGH_RES_LAYER,
             Wireframe2d,
@@ -44,6 +99,46 @@ impl Collider {
     }
 }
 
+/// Triangulates a convex polygon as a fan.
+fn polygon_mesh(vertices: &[Vec2]) -> Mesh {
+    let positions = vertices.iter().map(|v| [v.x, v.y, 0.]).collect::<Vec<_>>();
+    let indices = (2..vertices.len() as u32)
+        .flat_map(|i| [0, i - 1, i])
+        .collect::<Vec<_>>();
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Names a trigger and the layers it belongs to, above its collider.
+fn trigger_label(
+    entity: Entity,
+    trigger: &Trigger,
+    layers: &CollisionLayers,
+    name: Option<&Name>,
+) -> impl Bundle {
+    let name = name.map_or_else(|| entity.to_string(), |name| name.to_string());
+    let collider = trigger.0.absolute(&GlobalTransform::IDENTITY);
+    let top = Vec2::new((collider.min_x() + collider.max_x()) / 2., collider.max_y());
+
+    (
+        Text2d::new(format!("{name} {:#b}", layers.memberships)),
+        TextFont {
+            font_size: 24.,
+            ..Default::default()
+        },
+        TextColor(Srgba::GREEN.into()),
+        bevy::sprite::Anchor::BottomCenter,
+        // Rendered at a quarter scale so the text stays crisp on the high res camera.
+        Transform::from_translation(top.extend(999.)).with_scale(Vec3::splat(0.25)),
+        HIGH_RES_LAYER,
+    )
+}
+
 #[derive(Component)]
 pub struct DebugWireframe;
 
@@ -71,7 +166,10 @@ pub fn update_show_collision(
 
 pub fn debug_display_collider_wireframe(
     naked_colliders: Query<(Entity, &Collider), (Without<Marked>, With<Transform>)>,
-    naked_trigger_colliders: Query<(Entity, &Trigger), (Without<Marked>, With<Transform>)>,
+    naked_trigger_colliders: Query<
+        (Entity, &Trigger, &CollisionLayers, Option<&Name>),
+        (Without<Marked>, With<Transform>),
+    >,
     frames: Query<Entity, With<DebugWireframe>>,
     marked: Query<Entity, With<Marked>>,
     mut commands: Commands,
@@ -82,7 +180,7 @@ pub fn debug_display_collider_wireframe(
     if show.0 {
         for (entity, collider) in naked_colliders
             .iter()
-            .chain(naked_trigger_colliders.iter().map(|(e, t)| (e, &t.0)))
+            .chain(naked_trigger_colliders.iter().map(|(e, t, ..)| (e, &t.0)))
         {
             let wireframe = commands
                 .spawn((
@@ -95,6 +193,13 @@ pub fn debug_display_collider_wireframe(
                 .insert((Visibility::Visible, Marked))
                 .add_child(wireframe);
         }
+
+        for (entity, trigger, layers, name) in naked_trigger_colliders.iter() {
+            let label = commands
+                .spawn((DebugWireframe, trigger_label(entity, trigger, layers, name)))
+                .id();
+            commands.entity(entity).add_child(label);
+        }
     } else {
         for entity in frames.iter() {
             commands.entity(entity).despawn();
@@ -123,8 +228,8 @@ pub fn debug_show_trigger_color(
 }
 
 pub fn debug_show_collision_color(
-    static_bodies: Query<(&Transform, &Collider, &Children), With<StaticBody>>,
-    dynamic_bodies: Query<(&Transform, &Collider), With<DynamicBody>>,
+    static_bodies: Query<(&GlobalTransform, &Collider, &Children), With<StaticBody>>,
+    dynamic_bodies: Query<(&GlobalTransform, &Collider), With<DynamicBody>>,
     mut wireframes: Query<&mut Wireframe2dColor>,
 ) {
     for mut frame in wireframes.iter_mut() {
@@ -144,3 +249,114 @@ pub fn debug_show_collision_color(
         }
     }
 }
+
+/// Draws occupied [`SpatialHash`] cells, shaded by how many colliders they hold, every contact's
+/// resolution and lines from triggers to their targets.
+pub fn debug_draw_overlay(
+    mut gizmos: Gizmos<PhysicsGizmos>,
+    storages: Query<&SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    transforms: Query<&GlobalTransform>,
+    contacts: Res<Contacts>,
+    show: Res<ShowCollision>,
+) {
+    if !show.0 {
+        return;
+    }
+
+    for map in storages.iter() {
+        let size = map.cell_size();
+        for ((x, y), count) in map.cells() {
+            let crowding = (count as f32 / CROWDED_CELL as f32).min(1.);
+            gizmos.rect_2d(
+                Isometry2d::from_translation(Vec2::new(x as f32 + 0.5, y as f32 + 0.5) * size),
+                Vec2::splat(size),
+                Srgba::BLUE.mix(&Srgba::RED, crowding).with_alpha(0.5),
+            );
+        }
+    }
+
+    for contact in contacts.iter() {
+        let Ok(transform) = transforms.get(contact.entity) else {
+            continue;
+        };
+        let position = transform.translation().xy();
+
+        if contact.sensor {
+            if let Ok(target) = transforms.get(contact.other) {
+                gizmos.line_2d(position, target.translation().xy(), Srgba::GREEN);
+            }
+        } else {
+            gizmos.arrow_2d(
+                position,
+                position + contact.normal * (contact.penetration + NORMAL_LENGTH),
+                Srgba::RED,
+            );
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PhysicsStatsText;
+
+/// Shows the [`PhysicsStats`] and [`SpatialHash`] occupancy while the overlay is enabled.
+pub fn debug_show_stats(
+    mut commands: Commands,
+    text: Option<Single<(Entity, &mut Text), With<PhysicsStatsText>>>,
+    storages: Query<&SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    contacts: Res<Contacts>,
+    stats: Res<PhysicsStats>,
+    show: Res<ShowCollision>,
+) {
+    if !show.0 {
+        if let Some(text) = text {
+            commands.entity(text.0).despawn_recursive();
+        }
+        return;
+    }
+
+    let (mut bodies, mut cells, mut occupancy, mut crowded) = (0, 0, 0, 0);
+    for map in storages.iter() {
+        bodies += map.len();
+        for (_, count) in map.cells() {
+            cells += 1;
+            occupancy += count;
+            crowded = crowded.max(count);
+        }
+    }
+
+    let readout = format!(
+        "static bodies: {bodies}\n\
+         cells: {cells} ({:.1} avg, {crowded} max per cell)\n\
+         pairs tested: {}\n\
+         handle_collisions: {:.3} ms\n\
+         contacts: {}",
+        occupancy as f32 / cells.max(1) as f32,
+        stats.pairs_tested,
+        stats.collision_time.as_secs_f64() * 1000.,
+        contacts.iter().count(),
+    );
+
+    match text {
+        Some(mut text) => {
+            if text.1 .0 != readout {
+                text.1 .0 = readout;
+            }
+        }
+        None => {
+            commands.spawn((
+                PhysicsStatsText,
+                Text::new(readout),
+                TextFont {
+                    font_size: 16.,
+                    ..Default::default()
+                },
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(8.),
+                    left: Val::Px(8.),
+                    ..Default::default()
+                },
+            ));
+        }
+    }
+}
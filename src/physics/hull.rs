@@ -0,0 +1,267 @@
+use super::collision::{AbsoluteCollider, MAX_POLYGON_VERTICES};
+use super::sweep::SweepHit;
+use bevy::prelude::*;
+
+/// A convex polygon, segment or point, grown by `radius`.
+///
+/// Every [`AbsoluteCollider`] can be described as a hull, which lets shapes without a
+/// specialized implementation collide with each other.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RoundedHull {
+    vertices: [Vec2; MAX_POLYGON_VERTICES],
+    len: usize,
+    radius: f32,
+}
+
+/// Distance between hulls at which a [`RoundedHull::sweep`] reports contact.
+const SWEEP_TOLERANCE: f32 = 0.0001;
+
+/// The number of steps a [`RoundedHull::sweep`] may take to find the time of impact.
+const MAX_SWEEP_STEPS: usize = 32;
+
+impl RoundedHull {
+    /// `vertices` must wind counter-clockwise.
+    pub fn new(vertices: &[Vec2], radius: f32) -> Self {
+        let mut hull = Self {
+            vertices: [Vec2::ZERO; MAX_POLYGON_VERTICES],
+            len: vertices.len(),
+            radius,
+        };
+        hull.vertices[..vertices.len()].copy_from_slice(vertices);
+
+        hull
+    }
+
+    fn vertices(&self) -> &[Vec2] {
+        &self.vertices[..self.len]
+    }
+
+    fn translated(&self, offset: Vec2) -> Self {
+        let mut hull = *self;
+        hull.vertices[..hull.len]
+            .iter_mut()
+            .for_each(|v| *v += offset);
+
+        hull
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let vertices = self.vertices();
+        let count = match vertices.len() {
+            1 => 1,
+            2 => 1,
+            n => n,
+        };
+
+        (0..count).map(move |i| (vertices[i], vertices[(i + 1) % vertices.len()]))
+    }
+
+    /// Candidate separating axes.
+    fn axes(&self) -> impl Iterator<Item = Vec2> + '_ {
+        let segment = (self.len == 2).then(|| self.vertices[1] - self.vertices[0]);
+
+        self.edges()
+            .map(|(a, b)| (b - a).perp())
+            .chain(segment)
+            .filter_map(|axis| axis.try_normalize())
+    }
+
+    pub fn project(&self, axis: Vec2) -> (f32, f32) {
+        let (min, max) = self
+            .vertices()
+            .iter()
+            .map(|v| v.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| {
+                (min.min(p), max.max(p))
+            });
+
+        (min - self.radius, max + self.radius)
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        self.len >= 3 && self.edges().all(|(a, b)| (b - a).perp_dot(point - a) >= 0.)
+    }
+
+    /// The closest points between the unrounded hulls.
+    ///
+    /// Meaningless if the hulls overlap.
+    fn closest_points(&self, other: &Self) -> (Vec2, Vec2) {
+        self.edges()
+            .flat_map(|(a0, a1)| {
+                other
+                    .edges()
+                    .map(move |(b0, b1)| closest_points_on_segments(a0, a1, b0, b1))
+            })
+            .min_by(|(a0, b0), (a1, b1)| {
+                a0.distance_squared(*b0)
+                    .total_cmp(&a1.distance_squared(*b1))
+            })
+            .unwrap_or_default()
+    }
+
+    fn cores_overlap(&self, other: &Self) -> bool {
+        let (a, b) = self.closest_points(other);
+
+        a.distance_squared(b) <= f32::EPSILON
+            || other.contains_point(self.vertices[0])
+            || self.contains_point(other.vertices[0])
+    }
+
+    pub fn collides_with(&self, other: &Self) -> bool {
+        if self.cores_overlap(other) {
+            return true;
+        }
+
+        let (a, b) = self.closest_points(other);
+        a.distance(b) <= self.radius + other.radius
+    }
+
+    pub fn resolution(&self, other: &Self) -> Vec2 {
+        if self.cores_overlap(other) {
+            return self.penetration(other);
+        }
+
+        let (a, b) = self.closest_points(other);
+        let diff = a - b;
+        let distance = diff.length();
+        let combined_radii = self.radius + other.radius;
+
+        if distance >= combined_radii {
+            return Vec2::ZERO;
+        }
+
+        diff / distance * (combined_radii - distance)
+    }
+
+    /// Finds the minimum translation separating overlapping hulls with the separating axis
+    /// theorem.
+    fn penetration(&self, other: &Self) -> Vec2 {
+        let mut resolution = None::<Vec2>;
+
+        for axis in self.axes().chain(other.axes()) {
+            let (self_min, self_max) = self.project(axis);
+            let (other_min, other_max) = other.project(axis);
+
+            if self_max <= other_min || other_max <= self_min {
+                return Vec2::ZERO;
+            }
+
+            let forward = other_max - self_min;
+            let backward = self_max - other_min;
+            let push = if forward < backward {
+                axis * forward
+            } else {
+                -axis * backward
+            };
+
+            if resolution.is_none_or(|r| push.length_squared() < r.length_squared()) {
+                resolution = Some(push);
+            }
+        }
+
+        // Two points in the same position.
+        resolution.unwrap_or(Vec2::new(self.radius + other.radius, 0.))
+    }
+
+    /// Advances along `motion` until the hulls touch.
+    ///
+    /// The distance between moving convex shapes is convex in time, so stepping to where
+    /// the distance would reach zero at its current rate never passes the time of impact.
+    pub fn sweep(&self, motion: Vec2, other: &Self) -> Option<SweepHit> {
+        if self.collides_with(other) {
+            return None;
+        }
+
+        let mut time = 0.;
+        let mut normal = Vec2::ZERO;
+        for _ in 0..MAX_SWEEP_STEPS {
+            let (a, b) = self.translated(motion * time).closest_points(other);
+            let diff = a - b;
+            let distance = diff.length();
+
+            // Unrounded hulls can step exactly into contact.
+            if let Some(direction) = diff.try_normalize() {
+                normal = direction;
+            }
+
+            let gap = distance - self.radius - other.radius;
+            if gap <= SWEEP_TOLERANCE {
+                return Some(SweepHit { time, normal });
+            }
+
+            let closing_speed = -motion.dot(normal);
+            if closing_speed <= 0. {
+                return None;
+            }
+
+            time += gap / closing_speed;
+            if time > 1. {
+                return None;
+            }
+        }
+
+        None
+    }
+}
+
+impl From<&AbsoluteCollider> for RoundedHull {
+    fn from(collider: &AbsoluteCollider) -> Self {
+        match collider {
+            AbsoluteCollider::Rect(rect) => {
+                let (min, max) = (rect.tl, rect.br());
+                Self::new(
+                    &[min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)],
+                    0.,
+                )
+            }
+            AbsoluteCollider::Circle(circle) => Self::new(&[circle.position], circle.radius),
+            AbsoluteCollider::Capsule(capsule) => {
+                Self::new(&[capsule.start, capsule.end], capsule.radius)
+            }
+            AbsoluteCollider::Polygon(polygon) => Self::new(polygon.vertices(), 0.),
+        }
+    }
+}
+
+/// The closest points between the segments `a0`-`a1` and `b0`-`b1`.
+fn closest_points_on_segments(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> (Vec2, Vec2) {
+    let da = a1 - a0;
+    let db = b1 - b0;
+    let r = a0 - b0;
+    let la = da.length_squared();
+    let lb = db.length_squared();
+    let f = db.dot(r);
+
+    let (s, t) = if la <= f32::EPSILON && lb <= f32::EPSILON {
+        (0., 0.)
+    } else if la <= f32::EPSILON {
+        (0., (f / lb).clamp(0., 1.))
+    } else {
+        let c = da.dot(r);
+        if lb <= f32::EPSILON {
+            ((-c / la).clamp(0., 1.), 0.)
+        } else {
+            let b = da.dot(db);
+            let denom = la * lb - b * b;
+
+            let mut s = if denom != 0. {
+                ((b * f - c * lb) / denom).clamp(0., 1.)
+            } else {
+                0.
+            };
+
+            let mut t = (b * s + f) / lb;
+            if t < 0. {
+                t = 0.;
+                s = (-c / la).clamp(0., 1.);
+            } else if t > 1. {
+                t = 1.;
+                s = ((b - c) / la).clamp(0., 1.);
+            }
+
+            (s, t)
+        }
+    };
+
+    (a0 + da * s, b0 + db * t)
+}
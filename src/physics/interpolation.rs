@@ -0,0 +1,99 @@
+use super::sweep::ContinuousCollision;
+use bevy::prelude::*;
+
+/// The translation of a physics body as of the last physics tick.
+///
+/// Physics runs at a fixed rate, so rendered [`Transform`]s are interpolated between the
+/// [`PreviousPosition`] and [`Position`] every frame. Writing to the [`Transform`] outside of the
+/// physics schedule teleports the body there on the next tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+#[require(PreviousPosition, RenderedPosition)]
+pub struct Position(pub Vec2);
+
+/// The translation of a physics body as of the physics tick before last.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+pub struct PreviousPosition(pub Vec2);
+
+/// The translation last written to the [`Transform`] by physics.
+///
+/// Anything else means the body was moved outside of the physics schedule.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct RenderedPosition(Option<Vec2>);
+
+/// Enables interpolating rendered [`Transform`]s between physics ticks.
+///
+/// When disabled, bodies are rendered at their latest [`Position`].
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct PhysicsInterpolation(pub bool);
+
+impl Default for PhysicsInterpolation {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Replaces the interpolated translation with the physics state before stepping.
+pub fn restore_positions(
+    mut bodies: Query<(
+        &mut Transform,
+        &mut Position,
+        &mut PreviousPosition,
+        &RenderedPosition,
+        Option<&mut ContinuousCollision>,
+    )>,
+) {
+    for (mut transform, mut position, mut previous, rendered, continuous) in bodies.iter_mut() {
+        let translation = transform.translation.xy();
+
+        if rendered.0 != Some(translation) {
+            position.0 = translation;
+
+            // Teleported, so there is nothing to sweep across.
+            if let Some(mut continuous) = continuous {
+                continuous.reset();
+            }
+        } else if translation != position.0 {
+            transform.translation = position.0.extend(transform.translation.z);
+        }
+
+        previous.0 = position.0;
+    }
+}
+
+pub fn store_positions(mut bodies: Query<(&Transform, &mut Position, &mut RenderedPosition)>) {
+    for (transform, mut position, mut rendered) in bodies.iter_mut() {
+        position.0 = transform.translation.xy();
+        rendered.0 = Some(position.0);
+    }
+}
+
+pub fn interpolate_transforms(
+    mut bodies: Query<(
+        &mut Transform,
+        &Position,
+        &PreviousPosition,
+        &mut RenderedPosition,
+    )>,
+    interpolation: Res<PhysicsInterpolation>,
+    time: Res<Time<Fixed>>,
+) {
+    let alpha = if interpolation.0 {
+        time.overstep_fraction()
+    } else {
+        1.
+    };
+
+    for (mut transform, position, previous, mut rendered) in bodies.iter_mut() {
+        // Moved outside of physics, picked up next tick.
+        if rendered.0 != Some(transform.translation.xy()) {
+            continue;
+        }
+
+        let translation = previous.0.lerp(position.0, alpha);
+        if translation != transform.translation.xy() {
+            transform.translation = translation.extend(transform.translation.z);
+        }
+
+        rendered.0 = Some(translation);
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+
+/// Bitmask collision categories.
+///
+/// Two colliders interact when each is a member of a category the other filters for. Solid
+/// bodies that do not interact pass through one another, and [`Trigger`](super::trigger::Trigger)s
+/// only fire for colliders they interact with.
+///
+/// By default, colliders are members of [`CollisionLayers::DEFAULT`] and interact with everything.
+/// Triggers meant for the player alone, like interactions and doors, filter for
+/// [`CollisionLayers::PLAYER`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct CollisionLayers {
+    /// The categories this collider belongs to.
+    pub memberships: u32,
+    /// The categories this collider interacts with.
+    pub filters: u32,
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT, Self::ALL)
+    }
+}
+
+#[allow(unused)]
+impl CollisionLayers {
+    pub const NONE: u32 = 0;
+    pub const DEFAULT: u32 = 1;
+    pub const PLAYER: u32 = 1 << 1;
+    pub const ALL: u32 = u32::MAX;
+
+    pub const fn new(memberships: u32, filters: u32) -> Self {
+        Self {
+            memberships,
+            filters,
+        }
+    }
+
+    /// Only interacts with members of [`CollisionLayers::PLAYER`].
+    pub const fn player_only() -> Self {
+        Self::new(Self::DEFAULT, Self::PLAYER)
+    }
+
+    /// Interacts with nothing, and nothing interacts with it.
+    pub const fn none() -> Self {
+        Self::new(Self::NONE, Self::NONE)
+    }
+
+    pub const fn interacts_with(&self, other: &Self) -> bool {
+        self.memberships & other.filters != 0 && other.memberships & self.filters != 0
+    }
+}
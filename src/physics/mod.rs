@@ -1,16 +1,34 @@
+use crate::annual;
 use bevy::app::FixedMainScheduleOrder;
 use bevy::sprite::Wireframe2dPlugin;
+use bevy::transform;
 use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
 
 pub mod collision;
+pub mod contact;
+pub mod controller;
 pub mod debug;
+mod hull;
+pub mod interpolation;
+pub mod layers;
+pub mod query;
 mod spatial;
+pub mod sweep;
 pub mod trigger;
 pub mod velocity;
 
+#[cfg(test)]
+mod tests;
+
 #[allow(unused)]
 pub mod prelude {
     pub use super::collision::*;
+    pub use super::contact::*;
+    pub use super::controller::*;
+    pub use super::interpolation::*;
+    pub use super::layers::*;
+    pub use super::query::*;
+    pub use super::sweep::*;
     pub use super::trigger::*;
     pub use super::velocity::*;
 }
@@ -20,8 +38,17 @@ pub struct Physics;
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, SystemSet)]
 pub enum PhysicsSystems {
+    /// Restores the physics state of bodies that were interpolated since the last tick.
+    Prepare,
     Collision,
+    /// Brings [`GlobalTransform`]s up to date after velocity integration.
+    ///
+    /// Colliders are placed with the [`GlobalTransform`], which is otherwise only propagated
+    /// once per frame.
+    Propagate,
     Velocity,
+    /// Stores the physics state of bodies for interpolation.
+    Writeback,
 }
 
 #[derive(Debug)]
@@ -36,21 +63,71 @@ impl Plugin for PhysicsPlugin {
 
         app.add_plugins(Wireframe2dPlugin)
             .add_event::<trigger::TriggerEvent>()
-            .insert_resource(trigger::TriggerLayerRegistry::default())
+            .add_event::<contact::CollisionStarted>()
+            .add_event::<contact::CollisionOngoing>()
+            .add_event::<contact::CollisionEnded>()
+            .insert_resource(contact::Contacts::default())
+            .insert_resource(collision::SolverSettings::default())
+            .insert_resource(interpolation::PhysicsInterpolation::default())
             .insert_resource(debug::ShowCollision(false))
+            .insert_resource(debug::PhysicsStats::default())
+            .insert_gizmo_config(debug::PhysicsGizmos, debug::PhysicsGizmos::config())
+            .register_required_components::<bevy_ldtk_scene::Level, spatial::StaticBodyStorage>()
+            .register_required_components_with::<annual::OneWayUp, _>(|| {
+                collision::OneWayTile(IVec2::Y)
+            })
+            .register_required_components_with::<annual::OneWayDown, _>(|| {
+                collision::OneWayTile(IVec2::NEG_Y)
+            })
+            .register_required_components_with::<annual::OneWayLeft, _>(|| {
+                collision::OneWayTile(IVec2::NEG_X)
+            })
+            .register_required_components_with::<annual::OneWayRight, _>(|| {
+                collision::OneWayTile(IVec2::X)
+            })
             .add_systems(Startup, spatial::init_static_body_storage)
-            .add_systems(Update, collision::build_tile_set_colliders)
+            .add_systems(
+                Update,
+                (
+                    collision::build_tile_set_colliders,
+                    debug::debug_draw_overlay,
+                    debug::debug_show_stats,
+                ),
+            )
+            .add_systems(
+                RunFixedMainLoop,
+                interpolation::interpolate_transforms
+                    .in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+            )
+            .configure_sets(
+                Physics,
+                (
+                    PhysicsSystems::Prepare,
+                    PhysicsSystems::Velocity,
+                    PhysicsSystems::Propagate,
+                    PhysicsSystems::Collision,
+                    PhysicsSystems::Writeback,
+                )
+                    .chain(),
+            )
             .add_systems(
                 Physics,
                 (
                     (
-                        (trigger::register_trigger_layers, trigger::handle_triggers),
+                        trigger::handle_triggers,
                         (
+                            spatial::update_static_body_in_spatial_map,
                             spatial::store_static_body_in_spatial_map,
+                            controller::move_kinematic_controllers,
+                            sweep::handle_continuous_collisions,
                             collision::handle_collisions,
                             collision::handle_dynamic_body_collsions,
+                            sweep::store_continuous_collision_origin,
                         )
                             .chain(),
+                        contact::emit_collision_events
+                            .after(trigger::handle_triggers)
+                            .after(collision::handle_dynamic_body_collsions),
                         debug::debug_display_collider_wireframe,
                         debug::update_show_collision,
                         (
@@ -60,7 +137,16 @@ impl Plugin for PhysicsPlugin {
                             .chain(),
                     )
                         .in_set(PhysicsSystems::Collision),
-                    velocity::apply_velocity.in_set(PhysicsSystems::Velocity),
+                    (velocity::integrate_velocity, velocity::apply_velocity)
+                        .chain()
+                        .in_set(PhysicsSystems::Velocity),
+                    (
+                        transform::systems::sync_simple_transforms,
+                        transform::systems::propagate_transforms,
+                    )
+                        .in_set(PhysicsSystems::Propagate),
+                    interpolation::restore_positions.in_set(PhysicsSystems::Prepare),
+                    interpolation::store_positions.in_set(PhysicsSystems::Writeback),
                 ),
             );
     }
@@ -0,0 +1,235 @@
+use super::collision::{AbsoluteCollider, CircleCollider, Collider, CollidesWith, DynamicBody};
+use super::layers::CollisionLayers;
+use super::spatial::{SpatialHash, StaticBodyData, StaticBodyStorage};
+use super::sweep::{swept_bounds, Sweep};
+use super::trigger::Trigger;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// Selects which colliders a [`PhysicsQuery`] considers.
+#[derive(Debug, Clone)]
+pub struct QueryFilter {
+    /// Only match colliders that are members of these [`CollisionLayers`] categories.
+    pub mask: u32,
+    /// Never match these entities, e.g. the entity performing the query.
+    pub excluded: Vec<Entity>,
+    pub static_bodies: bool,
+    pub dynamic_bodies: bool,
+    pub triggers: bool,
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        Self {
+            mask: CollisionLayers::ALL,
+            excluded: Vec::new(),
+            static_bodies: true,
+            dynamic_bodies: true,
+            triggers: true,
+        }
+    }
+}
+
+impl QueryFilter {
+    pub fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    pub fn excluding(mut self, entity: Entity) -> Self {
+        self.excluded.push(entity);
+        self
+    }
+
+    /// Only match solid bodies.
+    pub fn bodies() -> Self {
+        Self {
+            triggers: false,
+            ..Default::default()
+        }
+    }
+
+    /// Only match [`Trigger`]s.
+    pub fn triggers() -> Self {
+        Self {
+            static_bodies: false,
+            dynamic_bodies: false,
+            ..Default::default()
+        }
+    }
+
+    fn matches(&self, entity: Entity, layers: &CollisionLayers) -> bool {
+        !self.excluded.contains(&entity) && layers.memberships & self.mask != 0
+    }
+}
+
+/// The first collider hit by a [`PhysicsQuery`] cast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CastHit {
+    pub entity: Entity,
+    /// Distance travelled along the cast before contact.
+    pub distance: f32,
+    /// Position of the cast origin at contact.
+    ///
+    /// For rays, this is the point of contact.
+    pub point: Vec2,
+    /// Surface normal of the contact, pointing back towards the cast.
+    pub normal: Vec2,
+}
+
+/// Spatial queries against static bodies, dynamic bodies and triggers.
+///
+/// Colliders that already contain the origin of a cast are not hit.
+#[derive(SystemParam)]
+pub struct PhysicsQuery<'w, 's> {
+    static_bodies: Query<'w, 's, &'static SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    dynamic_bodies: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            &'static Collider,
+            &'static CollisionLayers,
+        ),
+        With<DynamicBody>,
+    >,
+    triggers: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            &'static Trigger,
+            &'static CollisionLayers,
+        ),
+    >,
+}
+
+impl PhysicsQuery<'_, '_> {
+    /// Casts a ray from `origin` and returns the first collider it hits within `max_distance`.
+    pub fn raycast(
+        &self,
+        origin: Vec2,
+        direction: Dir2,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<CastHit> {
+        let ray = AbsoluteCollider::Circle(CircleCollider {
+            position: origin,
+            radius: 0.,
+        });
+
+        self.cast(ray, origin, direction, max_distance, filter)
+    }
+
+    /// Moves `shape`, relative to `origin`, along `direction` and returns the first collider it
+    /// hits within `max_distance`.
+    pub fn shape_cast(
+        &self,
+        shape: &Collider,
+        origin: Vec2,
+        direction: Dir2,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<CastHit> {
+        let shape = shape.absolute(&GlobalTransform::from_translation(origin.extend(0.)));
+        self.cast(shape, origin, direction, max_distance, filter)
+    }
+
+    /// Returns every collider containing `point`.
+    pub fn overlap_point(&self, point: Vec2, filter: &QueryFilter) -> Vec<Entity> {
+        let point_collider = AbsoluteCollider::Circle(CircleCollider {
+            position: point,
+            radius: 0.,
+        });
+
+        self.overlap(&point_collider, filter)
+    }
+
+    /// Returns every collider overlapping `shape`, relative to `origin`.
+    pub fn overlap_shape(
+        &self,
+        shape: &Collider,
+        origin: Vec2,
+        filter: &QueryFilter,
+    ) -> Vec<Entity> {
+        let shape = shape.absolute(&GlobalTransform::from_translation(origin.extend(0.)));
+        self.overlap(&shape, filter)
+    }
+
+    fn overlap(&self, shape: &AbsoluteCollider, filter: &QueryFilter) -> Vec<Entity> {
+        let (min, max) = swept_bounds(shape, Vec2::ZERO);
+
+        let mut entities = self
+            .candidates(min, max, filter)
+            .filter_map(|(entity, collider)| shape.collides_with(&collider).then_some(entity))
+            .collect::<Vec<_>>();
+
+        // Static bodies are stored in every cell they overlap.
+        entities.sort();
+        entities.dedup();
+
+        entities
+    }
+
+    fn cast(
+        &self,
+        shape: AbsoluteCollider,
+        origin: Vec2,
+        direction: Dir2,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<CastHit> {
+        let motion = direction * max_distance;
+        let (min, max) = swept_bounds(&shape, motion);
+
+        self.candidates(min, max, filter)
+            .filter_map(|(entity, collider)| {
+                shape.sweep(motion, &collider).map(|hit| (entity, hit))
+            })
+            .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time))
+            .map(|(entity, hit)| CastHit {
+                entity,
+                distance: hit.time * max_distance,
+                point: origin + motion * hit.time,
+                normal: hit.normal,
+            })
+    }
+
+    fn candidates<'a>(
+        &'a self,
+        min: Vec2,
+        max: Vec2,
+        filter: &'a QueryFilter,
+    ) -> impl Iterator<Item = (Entity, AbsoluteCollider)> + 'a {
+        let static_bodies = self
+            .static_bodies
+            .iter()
+            .filter(move |_| filter.static_bodies)
+            .flat_map(move |map| map.objects_in_rect(&min, &max))
+            .filter(move |d| filter.matches(d.entity, &d.data.layers))
+            .map(|d| (d.entity, d.collider));
+
+        let dynamic_bodies = self
+            .dynamic_bodies
+            .iter()
+            .filter(move |_| filter.dynamic_bodies)
+            .filter(move |(entity, _, _, layer)| filter.matches(*entity, layer))
+            .map(|(entity, transform, collider, _)| (entity, collider.absolute(transform)));
+
+        let triggers = self
+            .triggers
+            .iter()
+            .filter(move |_| filter.triggers)
+            .filter(move |(entity, _, _, layer)| filter.matches(*entity, layer))
+            .map(|(entity, transform, trigger, _)| (entity, trigger.0.absolute(transform)));
+
+        static_bodies.chain(dynamic_bodies.chain(triggers).filter(move |(_, collider)| {
+            collider.min_x() <= max.x
+                && collider.max_x() >= min.x
+                && collider.min_y() <= max.y
+                && collider.max_y() >= min.y
+        }))
+    }
+}
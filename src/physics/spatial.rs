@@ -1,6 +1,9 @@
 use super::{
-    collision::AbsoluteCollider, collision::Collider, collision::StaticBody, trigger::TriggerLayer,
+    collision::AbsoluteCollider, collision::Collider, collision::CollisionEdges, collision::OneWay,
+    collision::StaticBody, layers::CollisionLayers,
 };
+use bevy::ecs::component::ComponentId;
+use bevy::ecs::world::DeferredWorld;
 use bevy::{prelude::*, utils::hashbrown::HashMap};
 
 #[derive(Debug, Clone, Copy)]
@@ -13,7 +16,7 @@ pub struct SpatialData<D> {
 impl<D> SpatialData<D> {
     pub fn from_entity(
         entity: Entity,
-        transform: &Transform,
+        transform: &GlobalTransform,
         collider: &Collider,
         data: D,
     ) -> Self {
@@ -29,6 +32,7 @@ impl<D> SpatialData<D> {
 pub struct SpatialHash<D> {
     cell_size: f32,
     objects: HashMap<(i32, i32), Vec<SpatialData<D>>>,
+    entity_cells: HashMap<Entity, Vec<(i32, i32)>>,
 }
 
 #[allow(dead_code)]
@@ -37,6 +41,7 @@ impl<D: Clone> SpatialHash<D> {
         SpatialHash {
             cell_size,
             objects: HashMap::default(),
+            entity_cells: HashMap::default(),
         }
     }
 
@@ -56,20 +61,90 @@ impl<D: Clone> SpatialHash<D> {
 
     /// Inserts spatial data into map.
     ///
-    /// Data will be added to all cells overlapped by data's [`AbsoluteCollider`].
+    /// Data will be added to all cells overlapped by data's [`AbsoluteCollider`]. Inserting data
+    /// for an entity that is already in the map replaces the old data.
     pub fn insert(&mut self, data: SpatialData<D>) {
+        self.remove(data.entity);
+
         let (min_x, min_y) = self.hash(&Vec2::new(data.collider.min_x(), data.collider.min_y()));
         let (max_x, max_y) = self.hash(&Vec2::new(data.collider.max_x(), data.collider.max_y()));
 
+        let mut cells = Vec::with_capacity(((max_x - min_x + 1) * (max_y - min_y + 1)) as usize);
         for y in min_y..=max_y {
             for x in min_x..=max_x {
                 self.objects.entry((x, y)).or_default().push(data.clone());
+                cells.push((x, y));
+            }
+        }
+
+        self.entity_cells.insert(data.entity, cells);
+    }
+
+    /// Removes the entity's data from every cell it occupies.
+    pub fn remove(&mut self, entity: Entity) -> Option<SpatialData<D>> {
+        let cells = self.entity_cells.remove(&entity)?;
+
+        let mut removed = None;
+        for cell in cells.into_iter() {
+            if let Some(objects) = self.objects.get_mut(&cell) {
+                if let Some(index) = objects.iter().position(|d| d.entity == entity) {
+                    removed = Some(objects.remove(index));
+                }
+
+                if objects.is_empty() {
+                    self.objects.remove(&cell);
+                }
             }
         }
+
+        removed
+    }
+
+    /// Moves the entity's data into the cells overlapped by `collider`.
+    ///
+    /// Returns `false` if the entity is not in the map.
+    pub fn update(&mut self, entity: Entity, collider: AbsoluteCollider) -> bool {
+        match self.remove(entity) {
+            Some(mut data) => {
+                data.collider = collider;
+                self.insert(data);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&SpatialData<D>> {
+        let cell = self.entity_cells.get(&entity)?.first()?;
+
+        self.objects
+            .get(cell)
+            .and_then(|objects| objects.iter().find(|d| d.entity == entity))
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Iterates over every occupied cell and the number of objects in it.
+    pub fn cells(&self) -> impl Iterator<Item = ((i32, i32), usize)> + '_ {
+        self.objects
+            .iter()
+            .map(|(cell, objects)| (*cell, objects.len()))
+    }
+
+    /// The number of entities in the map.
+    pub fn len(&self) -> usize {
+        self.entity_cells.len()
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entity_cells.contains_key(&entity)
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.entity_cells.clear();
     }
 
     pub fn objects_in_cell_mut<'a>(
@@ -100,6 +175,22 @@ impl<D: Clone> SpatialHash<D> {
         })
     }
 
+    /// Iterates over the data in every cell overlapped by the rectangle spanning `min` to `max`.
+    ///
+    /// Data that spans multiple cells will be yielded once per cell.
+    pub fn objects_in_rect<'a>(
+        &'a self,
+        min: &Vec2,
+        max: &Vec2,
+    ) -> impl Iterator<Item = &'a SpatialData<D>> + 'a {
+        let (min_x, min_y) = self.hash(min);
+        let (max_x, max_y) = self.hash(max);
+
+        (min_y..=max_y).flat_map(move |y| {
+            (min_x..=max_x).flat_map(move |x| self.objects.get(&(x, y)).into_iter().flatten())
+        })
+    }
+
     pub fn nearby_objects_mut<'a>(
         &'a mut self,
         position: &Vec2,
@@ -116,26 +207,115 @@ impl<D: Clone> SpatialHash<D> {
     }
 }
 
-#[derive(Component)]
+/// Stores the [`StaticBody`] entities of a level.
+///
+/// Every [`bevy_ldtk_scene::Level`] requires a storage, so its colliders are torn down
+/// along with the level. Static bodies are stored in their nearest ancestor's storage, or
+/// the [`GlobalStaticBodyStorage`] if they are not part of a level.
+#[derive(Default, Component)]
+#[require(SpatialHash<StaticBodyData>(|| SpatialHash::new(32.)))]
 pub struct StaticBodyStorage;
 
-pub type StaticBodyData = Option<TriggerLayer>;
+/// Marks the [`StaticBodyStorage`] for static bodies spawned outside of a level.
+#[derive(Default, Component)]
+#[require(StaticBodyStorage)]
+pub struct GlobalStaticBodyStorage;
+
+#[derive(Debug, Clone, Copy)]
+pub struct StaticBodyData {
+    pub layers: CollisionLayers,
+    pub edges: CollisionEdges,
+    pub one_way: Option<OneWay>,
+}
+
+impl StaticBodyData {
+    /// Whether a body that started the tick as `previous` should be pushed out of `collider`
+    /// along `direction`.
+    pub fn blocks(
+        &self,
+        collider: &AbsoluteCollider,
+        previous: &AbsoluteCollider,
+        direction: Vec2,
+    ) -> bool {
+        self.edges.allows(direction)
+            && self
+                .one_way
+                .is_none_or(|one_way| one_way.blocks(collider, previous))
+    }
+}
 
-// TODO: should there be a spatial hash for each level?
 pub fn init_static_body_storage(mut commands: Commands) {
-    commands.spawn((SpatialHash::<StaticBodyData>::new(32.), StaticBodyStorage));
+    commands.spawn(GlobalStaticBodyStorage);
+}
+
+pub fn remove_static_body_from_spatial_map(
+    mut world: DeferredWorld,
+    entity: Entity,
+    _: ComponentId,
+) {
+    world.commands().queue(move |world: &mut World| {
+        let mut maps =
+            world.query_filtered::<&mut SpatialHash<StaticBodyData>, With<StaticBodyStorage>>();
+        for mut map in maps.iter_mut(world) {
+            map.remove(entity);
+        }
+    });
+}
+
+pub fn update_static_body_in_spatial_map(
+    mut maps: Query<&mut SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    static_body: Query<
+        (Entity, &GlobalTransform, &Collider),
+        (
+            With<StaticBody>,
+            Or<(Changed<GlobalTransform>, Changed<Collider>)>,
+        ),
+    >,
+) {
+    for (entity, transform, collider) in static_body.iter() {
+        let collider = collider.absolute(transform);
+
+        for mut map in maps.iter_mut() {
+            // Transforms are frequently touched without moving, e.g. by z ordering.
+            if map.get(entity).is_some_and(|d| d.collider != collider) {
+                map.update(entity, collider);
+            }
+        }
+    }
 }
 
 pub fn store_static_body_in_spatial_map(
-    map: Single<&mut SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
-    static_body: Query<(Entity, &Transform, &Collider, Option<&TriggerLayer>), Added<StaticBody>>,
+    mut maps: Query<&mut SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    global: Single<Entity, With<GlobalStaticBodyStorage>>,
+    static_body: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Collider,
+            &CollisionLayers,
+            Option<&CollisionEdges>,
+            Option<&OneWay>,
+        ),
+        Added<StaticBody>,
+    >,
+    parents: Query<&Parent>,
 ) {
-    let mut map = map.into_inner();
-    for (entity, transform, collider, trigger_layer) in static_body.iter() {
-        map.insert(SpatialData {
-            collider: collider.absolute(transform),
-            data: trigger_layer.cloned(),
-            entity,
-        })
+    for (entity, transform, collider, layers, edges, one_way) in static_body.iter() {
+        let storage = parents
+            .iter_ancestors(entity)
+            .find(|ancestor| maps.contains(*ancestor))
+            .unwrap_or(*global);
+
+        if let Ok(mut map) = maps.get_mut(storage) {
+            map.insert(SpatialData {
+                collider: collider.absolute(transform),
+                data: StaticBodyData {
+                    layers: *layers,
+                    edges: edges.copied().unwrap_or_default(),
+                    one_way: one_way.copied(),
+                },
+                entity,
+            })
+        }
     }
 }
@@ -0,0 +1,325 @@
+use super::collision::{
+    translate_global, AbsoluteCollider, CircleCollider, Collider, DynamicBody, RectCollider,
+};
+use super::contact::{Contact, Contacts};
+use super::hull::RoundedHull;
+use super::layers::CollisionLayers;
+use super::spatial::{SpatialHash, StaticBodyData, StaticBodyStorage};
+use bevy::prelude::*;
+
+/// Opts a [`DynamicBody`] into swept collision against static bodies.
+///
+/// The motion between physics ticks is swept along a segment and stopped at the first
+/// [`StaticBody`](super::collision::StaticBody) it would hit, so fast movers can not tunnel
+/// through thin colliders. Remaining motion slides along the surface that was hit.
+///
+/// Bodies moved outside of the physics schedule, like when a save is loaded, are not swept
+/// from where they were. See [`Position`](super::interpolation::Position).
+#[derive(Debug, Default, Clone, Copy, Component)]
+#[require(DynamicBody)]
+pub struct ContinuousCollision {
+    previous: Option<Vec2>,
+}
+
+impl ContinuousCollision {
+    /// Skips the next sweep, starting over from wherever the body is.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// The first point of contact found by a [`Sweep`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit {
+    /// Fraction of the motion, in `0..=1`, travelled before contact.
+    pub time: f32,
+    /// Surface normal of the contact, pointing towards the swept collider.
+    pub normal: Vec2,
+}
+
+/// Computes the time of impact of a collider moving along `motion` against a stationary `other`.
+///
+/// Colliders that already overlap at the start of the motion do not produce a hit. Resolving
+/// overlap is left to [`CollidesWith::resolution`](super::collision::CollidesWith::resolution).
+pub trait Sweep<T> {
+    fn sweep(&self, motion: Vec2, other: &T) -> Option<SweepHit>;
+}
+
+impl Sweep<Self> for AbsoluteCollider {
+    fn sweep(&self, motion: Vec2, other: &Self) -> Option<SweepHit> {
+        match (self, other) {
+            (Self::Rect(s), Self::Rect(o)) => s.sweep(motion, o),
+            (Self::Rect(s), Self::Circle(o)) => s.sweep(motion, o),
+            (Self::Circle(s), Self::Rect(o)) => s.sweep(motion, o),
+            (Self::Circle(s), Self::Circle(o)) => s.sweep(motion, o),
+            (s, o) => RoundedHull::from(s).sweep(motion, &RoundedHull::from(o)),
+        }
+    }
+}
+
+impl Sweep<Self> for RectCollider {
+    fn sweep(&self, motion: Vec2, other: &Self) -> Option<SweepHit> {
+        // Sweep the top left corner against `other` grown by our size.
+        ray_rect(self.tl, motion, other.tl - self.size, other.br())
+    }
+}
+
+impl Sweep<RectCollider> for CircleCollider {
+    fn sweep(&self, motion: Vec2, other: &RectCollider) -> Option<SweepHit> {
+        let r = self.radius;
+        let (min, max) = (other.tl, other.br());
+
+        if overlaps_rounded_rect(self.position, min, max, r) {
+            return None;
+        }
+
+        // The rect grown by the radius is the union of two crossed rects and four corner circles.
+        let parts = [
+            ray_rect(
+                self.position,
+                motion,
+                Vec2::new(min.x - r, min.y),
+                Vec2::new(max.x + r, max.y),
+            ),
+            ray_rect(
+                self.position,
+                motion,
+                Vec2::new(min.x, min.y - r),
+                Vec2::new(max.x, max.y + r),
+            ),
+            ray_circle(self.position, motion, min, r),
+            ray_circle(self.position, motion, Vec2::new(max.x, min.y), r),
+            ray_circle(self.position, motion, Vec2::new(min.x, max.y), r),
+            ray_circle(self.position, motion, max, r),
+        ];
+
+        parts
+            .into_iter()
+            .flatten()
+            .min_by(|a, b| a.time.total_cmp(&b.time))
+    }
+}
+
+impl Sweep<CircleCollider> for RectCollider {
+    fn sweep(&self, motion: Vec2, other: &CircleCollider) -> Option<SweepHit> {
+        // Equivalent to the circle moving the opposite direction into the rect.
+        other.sweep(-motion, self).map(|hit| SweepHit {
+            time: hit.time,
+            normal: -hit.normal,
+        })
+    }
+}
+
+impl Sweep<Self> for CircleCollider {
+    fn sweep(&self, motion: Vec2, other: &Self) -> Option<SweepHit> {
+        ray_circle(
+            self.position,
+            motion,
+            other.position,
+            self.radius + other.radius,
+        )
+    }
+}
+
+/// Strictly inside the rect grown by `r`, with rounded corners.
+fn overlaps_rounded_rect(point: Vec2, min: Vec2, max: Vec2, r: f32) -> bool {
+    let closest = point.clamp(min, max);
+    point.distance_squared(closest) < r * r
+}
+
+/// Intersects the segment `origin + motion * t`, `t` in `0..=1`, with the rect spanning `min`
+/// to `max`.
+///
+/// Segments that start strictly inside the rect, or only graze its edges, do not hit.
+pub(super) fn ray_rect(origin: Vec2, motion: Vec2, min: Vec2, max: Vec2) -> Option<SweepHit> {
+    let mut enter = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+
+    for axis in 0..2 {
+        let (o, d, lo, hi) = (origin[axis], motion[axis], min[axis], max[axis]);
+
+        if d == 0. {
+            if o <= lo || o >= hi {
+                return None;
+            }
+            continue;
+        }
+
+        let (near, far, sign) = if d > 0. {
+            ((lo - o) / d, (hi - o) / d, -1.)
+        } else {
+            ((hi - o) / d, (lo - o) / d, 1.)
+        };
+
+        if near > enter {
+            enter = near;
+            normal = Vec2::ZERO;
+            normal[axis] = sign;
+        }
+        exit = exit.min(far);
+    }
+
+    (enter < exit && (0. ..=1.).contains(&enter)).then_some(SweepHit {
+        time: enter,
+        normal,
+    })
+}
+
+/// Intersects the segment `origin + motion * t`, `t` in `0..=1`, with the circle at `center`.
+///
+/// Segments that start strictly inside the circle do not hit.
+pub(super) fn ray_circle(
+    origin: Vec2,
+    motion: Vec2,
+    center: Vec2,
+    radius: f32,
+) -> Option<SweepHit> {
+    let a = motion.length_squared();
+    if a == 0. {
+        return None;
+    }
+
+    let m = origin - center;
+    let b = m.dot(motion);
+    let c = m.length_squared() - radius * radius;
+
+    // Inside, or outside and moving away.
+    if c < 0. || b >= 0. {
+        return None;
+    }
+
+    let discriminant = b * b - a * c;
+    if discriminant < 0. {
+        return None;
+    }
+
+    let time = (-b - discriminant.sqrt()) / a;
+    (0. ..=1.).contains(&time).then(|| SweepHit {
+        time,
+        normal: (origin + motion * time - center).normalize_or_zero(),
+    })
+}
+
+/// The number of times a swept body may slide along a surface in a single tick.
+const MAX_SWEEP_ITERATIONS: usize = 4;
+
+/// Distance kept between a swept body and the surface it hit.
+const SKIN: f32 = 0.01;
+
+pub fn handle_continuous_collisions(
+    static_body_storage: Query<&SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    mut bodies: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut GlobalTransform,
+            &Collider,
+            &CollisionLayers,
+            &ContinuousCollision,
+        ),
+        With<DynamicBody>,
+    >,
+    mut contacts: ResMut<Contacts>,
+) {
+    for (entity, mut transform, mut global, collider, layers, continuous) in bodies.iter_mut() {
+        let Some(previous) = continuous.previous else {
+            continue;
+        };
+
+        let current = global.translation().xy();
+        let collider = collider.absolute(&global).translated(previous - current);
+
+        let offset = collide_and_slide(
+            &static_body_storage,
+            &collider,
+            layers,
+            current - previous,
+            |other, hit| {
+                contacts.insert(Contact {
+                    entity,
+                    other,
+                    normal: hit.normal,
+                    penetration: 0.,
+                    sensor: false,
+                })
+            },
+        );
+
+        let position = previous + offset;
+        if position != current {
+            translate_global(&mut transform, &mut global, position - current);
+        }
+    }
+}
+
+/// Moves `collider` along `motion`, stopping at the static bodies it interacts with and sliding
+/// the remaining motion along their surfaces.
+///
+/// Returns the offset travelled. `on_hit` is called for every surface hit along the way.
+pub(super) fn collide_and_slide(
+    static_body_storage: &Query<&SpatialHash<StaticBodyData>, With<StaticBodyStorage>>,
+    collider: &AbsoluteCollider,
+    layers: &CollisionLayers,
+    mut motion: Vec2,
+    mut on_hit: impl FnMut(Entity, SweepHit),
+) -> Vec2 {
+    let mut offset = Vec2::ZERO;
+
+    for _ in 0..MAX_SWEEP_ITERATIONS {
+        if motion == Vec2::ZERO {
+            break;
+        }
+
+        let swept = collider.translated(offset);
+        let (min, max) = swept_bounds(&swept, motion);
+
+        let hit = static_body_storage
+            .iter()
+            .flat_map(|map| map.objects_in_rect(&min, &max))
+            .filter(|d| layers.interacts_with(&d.data.layers))
+            .filter_map(|d| {
+                swept
+                    .sweep(motion, &d.collider)
+                    .filter(|hit| d.data.blocks(&d.collider, &swept, hit.normal))
+                    .map(|hit| (d.entity, hit))
+            })
+            .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time));
+
+        match hit {
+            Some((other, hit)) => {
+                on_hit(other, hit);
+                offset += motion * hit.time + hit.normal * SKIN;
+
+                // Slide the remaining motion along the surface.
+                let remaining = motion * (1. - hit.time);
+                motion = remaining - hit.normal * remaining.dot(hit.normal);
+            }
+            None => {
+                offset += motion;
+                motion = Vec2::ZERO;
+            }
+        }
+    }
+
+    offset
+}
+
+/// Records the resolved position of swept bodies as the start of next tick's sweep.
+pub fn store_continuous_collision_origin(
+    mut bodies: Query<(&GlobalTransform, &mut ContinuousCollision)>,
+) {
+    for (transform, mut continuous) in bodies.iter_mut() {
+        continuous.previous = Some(transform.translation().xy());
+    }
+}
+
+pub(super) fn swept_bounds(collider: &AbsoluteCollider, motion: Vec2) -> (Vec2, Vec2) {
+    let min = Vec2::new(collider.min_x(), collider.min_y());
+    let max = Vec2::new(collider.max_x(), collider.max_y());
+
+    (
+        min.min(min + motion) - Vec2::splat(SKIN),
+        max.max(max + motion) + Vec2::splat(SKIN),
+    )
+}
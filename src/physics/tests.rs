@@ -0,0 +1,902 @@
+use super::collision::build_colliders_from_vec2;
+use super::prelude::*;
+use super::sweep::{ray_circle, ray_rect};
+use super::{Physics, PhysicsPlugin};
+use bevy::gizmos::GizmoPlugin;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::utils::hashbrown::HashMap;
+
+/// A body in a [`Harness`].
+enum Body {
+    Static(Collider),
+    Dynamic(Collider),
+    /// A dynamic body with an initial [`Velocity`].
+    Moving(Collider, Vec2),
+    Trigger(Collider),
+}
+
+/// A headless [`App`] running the [`Physics`] schedule on named bodies.
+struct Harness {
+    app: App,
+    bodies: HashMap<&'static str, Entity>,
+    trigger_events: Vec<TriggerEvent>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut app = App::new();
+        // The debug overlay needs assets, input and gizmos, but nothing is rendered.
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), InputPlugin))
+            .init_asset::<Shader>()
+            .init_asset::<Mesh>()
+            .init_asset::<ColorMaterial>()
+            .add_plugins((GizmoPlugin, PhysicsPlugin));
+        app.finish();
+        app.cleanup();
+        // Runs the startup systems.
+        app.update();
+
+        Self {
+            app,
+            bodies: HashMap::default(),
+            trigger_events: Vec::new(),
+        }
+    }
+
+    fn with(mut self, name: &'static str, position: Vec2, body: Body) -> Self {
+        let transform = Transform::from_translation(position.extend(0.));
+        let mut entity = self.app.world_mut().spawn(transform);
+
+        match body {
+            Body::Static(collider) => entity.insert((StaticBody, collider)),
+            Body::Dynamic(collider) => entity.insert((DynamicBody, collider)),
+            Body::Moving(collider, velocity) => {
+                entity.insert((DynamicBody, collider, Velocity(velocity)))
+            }
+            Body::Trigger(collider) => entity.insert(Trigger(collider)),
+        };
+
+        self.bodies.insert(name, entity.id());
+        self
+    }
+
+    fn with_bundle(mut self, name: &'static str, bundle: impl Bundle) -> Self {
+        let entity = self.entity(name);
+        self.app.world_mut().entity_mut(entity).insert(bundle);
+        self
+    }
+
+    /// Moves a body under a new parent entity, keeping its [`Transform`] as the local transform.
+    fn with_parent(mut self, name: &'static str, parent: Transform) -> Self {
+        let entity = self.entity(name);
+        let world = self.app.world_mut();
+        let parent = world.spawn(parent).id();
+        world.entity_mut(entity).set_parent(parent);
+        self
+    }
+
+    fn entity(&self, name: &str) -> Entity {
+        self.bodies[name]
+    }
+
+    /// Runs the [`Physics`] schedule `ticks` times, one fixed timestep apart.
+    fn step(&mut self, ticks: usize) -> &mut Self {
+        let world = self.app.world_mut();
+
+        for _ in 0..ticks {
+            let mut time = world.resource_mut::<Time<Fixed>>();
+            let timestep = time.timestep();
+            time.advance_by(timestep);
+
+            world.run_schedule(Physics);
+            self.trigger_events
+                .extend(world.resource_mut::<Events<TriggerEvent>>().drain());
+        }
+
+        self
+    }
+
+    fn position(&self, name: &str) -> Vec2 {
+        self.app
+            .world()
+            .get::<Transform>(self.entity(name))
+            .unwrap()
+            .translation
+            .xy()
+    }
+
+    fn global_position(&self, name: &str) -> Vec2 {
+        self.app
+            .world()
+            .get::<GlobalTransform>(self.entity(name))
+            .unwrap()
+            .translation()
+            .xy()
+    }
+
+    fn contact(&self, a: &str, b: &str) -> Option<Contact> {
+        self.app
+            .world()
+            .resource::<Contacts>()
+            .get(self.entity(a), self.entity(b))
+            .copied()
+    }
+
+    fn triggered(&self, trigger: &str, target: &str) -> bool {
+        let (trigger, target) = (self.entity(trigger), self.entity(target));
+
+        self.trigger_events
+            .iter()
+            .any(|event| event.trigger == trigger && event.target == target)
+    }
+}
+
+#[track_caller]
+fn assert_near(actual: Vec2, expected: Vec2) {
+    assert!(
+        actual.abs_diff_eq(expected, 0.001),
+        "expected {expected}, found {actual}"
+    );
+}
+
+fn rect(tl: Vec2, size: Vec2) -> RectCollider {
+    RectCollider { tl, size }
+}
+
+fn circle(position: Vec2, radius: f32) -> CircleCollider {
+    CircleCollider { position, radius }
+}
+
+fn square(size: f32) -> Collider {
+    Collider::from_rect(Vec2::splat(-size / 2.), Vec2::splat(size))
+}
+
+#[test]
+fn rect_resolution_picks_the_shallower_axis() {
+    let wall = rect(Vec2::ZERO, Vec2::splat(16.));
+
+    assert_eq!(
+        rect(Vec2::new(14., 4.), Vec2::splat(8.)).resolution(&wall),
+        Vec2::new(2., 0.)
+    );
+    assert_eq!(
+        rect(Vec2::new(4., -6.), Vec2::splat(8.)).resolution(&wall),
+        Vec2::new(0., -2.)
+    );
+}
+
+#[test]
+fn rect_resolution_at_corners() {
+    let wall = rect(Vec2::ZERO, Vec2::splat(16.));
+
+    // Equal overlap on both axes resolves vertically.
+    assert_eq!(
+        rect(Vec2::new(14., 14.), Vec2::splat(8.)).resolution(&wall),
+        Vec2::new(0., 2.)
+    );
+    assert_eq!(
+        rect(Vec2::new(13., 14.), Vec2::splat(8.)).resolution(&wall),
+        Vec2::new(0., 2.)
+    );
+    assert_eq!(
+        rect(Vec2::new(14., 13.), Vec2::splat(8.)).resolution(&wall),
+        Vec2::new(2., 0.)
+    );
+}
+
+#[test]
+fn rect_resolution_of_touching_rects_is_zero() {
+    let wall = rect(Vec2::ZERO, Vec2::splat(16.));
+    let touching = rect(Vec2::new(16., 0.), Vec2::splat(8.));
+
+    assert!(touching.collides_with(&wall));
+    assert_eq!(touching.resolution(&wall), Vec2::ZERO);
+    assert_eq!(
+        rect(Vec2::new(16., 16.), Vec2::splat(8.)).resolution(&wall),
+        Vec2::ZERO
+    );
+}
+
+#[test]
+fn rect_resolution_of_contained_rects() {
+    let wall = rect(Vec2::ZERO, Vec2::new(32., 16.));
+
+    // Contained rects are pushed by their overlap, away from the other center.
+    assert_eq!(
+        rect(Vec2::new(2., 6.), Vec2::new(4., 4.)).resolution(&wall),
+        Vec2::new(0., 4.)
+    );
+    assert_eq!(
+        rect(Vec2::new(2., 2.), Vec2::new(4., 12.)).resolution(&wall),
+        Vec2::new(-4., 0.)
+    );
+
+    // Identical rects push up.
+    assert_eq!(wall.resolution(&wall), Vec2::new(0., 16.));
+}
+
+#[test]
+fn circle_rect_resolution_along_edges() {
+    let wall = rect(Vec2::ZERO, Vec2::splat(16.));
+
+    assert_near(
+        circle(Vec2::new(19., 8.), 4.).resolution(&wall),
+        Vec2::new(1., 0.),
+    );
+    assert_near(
+        circle(Vec2::new(8., -3.), 4.).resolution(&wall),
+        Vec2::new(0., -1.),
+    );
+    assert_eq!(circle(Vec2::new(20., 8.), 4.).resolution(&wall), Vec2::ZERO);
+}
+
+#[test]
+fn rect_circle_resolution_pushes_the_rect() {
+    let ball = circle(Vec2::ZERO, 4.);
+
+    assert_near(
+        rect(Vec2::new(3., -2.), Vec2::splat(4.)).resolution(&ball),
+        Vec2::new(1., 0.),
+    );
+    assert_near(
+        rect(Vec2::new(-2., -7.), Vec2::splat(4.)).resolution(&ball),
+        Vec2::new(0., -1.),
+    );
+
+    let mut harness = Harness::new()
+        .with(
+            "ball",
+            Vec2::ZERO,
+            Body::Static(Collider::from_circle(Vec2::ZERO, 4.)),
+        )
+        .with("box", Vec2::new(7., 0.), Body::Dynamic(square(8.)));
+
+    harness.step(1);
+    assert_near(harness.position("box"), Vec2::new(8., 0.));
+}
+
+#[test]
+fn resolution_is_symmetric() {
+    let rect = AbsoluteCollider::Rect(rect(Vec2::ZERO, Vec2::new(16., 8.)));
+    let ball = AbsoluteCollider::Circle(circle(Vec2::new(19., 4.), 4.));
+    let capsule = AbsoluteCollider::Capsule(CapsuleCollider {
+        start: Vec2::new(4., 10.),
+        end: Vec2::new(22., 10.),
+        radius: 3.,
+    });
+
+    for (a, b) in [(&rect, &ball), (&rect, &capsule), (&ball, &capsule)] {
+        let resolution = a.resolution(b);
+        assert_ne!(resolution, Vec2::ZERO);
+        assert_near(resolution, -b.resolution(a));
+    }
+}
+
+#[test]
+fn circle_rect_resolution_at_corners() {
+    let wall = rect(Vec2::ZERO, Vec2::splat(16.));
+    let corner = Vec2::splat(16.);
+    let circle = circle(corner + Vec2::splat(2.), 4.);
+
+    let resolution = circle.resolution(&wall);
+    assert_near(resolution, Vec2::splat(2.).normalize() * (4. - 8f32.sqrt()));
+
+    assert_near(wall.resolution(&circle), -resolution);
+
+    // Diagonally past the corner, but within the bounding box.
+    let clear = CircleCollider {
+        position: corner + Vec2::splat(3.),
+        ..circle
+    };
+    assert!(!clear.collides_with(&wall));
+    assert_eq!(clear.resolution(&wall), Vec2::ZERO);
+}
+
+#[test]
+fn circle_rect_resolution_from_inside() {
+    let wall = rect(Vec2::ZERO, Vec2::new(32., 16.));
+
+    // Pushed by the radius towards the nearest edge.
+    assert_eq!(
+        circle(Vec2::new(3., 8.), 2.).resolution(&wall),
+        Vec2::new(-2., 0.)
+    );
+    assert_eq!(
+        circle(Vec2::new(16., 13.), 2.).resolution(&wall),
+        Vec2::new(0., 2.)
+    );
+    // On the edge.
+    assert_eq!(
+        circle(Vec2::new(32., 8.), 2.).resolution(&wall),
+        Vec2::new(2., 0.)
+    );
+}
+
+#[test]
+fn dynamic_bodies_are_pushed_out_of_static_bodies() {
+    let mut harness = Harness::new()
+        .with("wall", Vec2::ZERO, Body::Static(square(16.)))
+        .with("box", Vec2::new(10., 2.), Body::Dynamic(square(8.)))
+        .with(
+            "ball",
+            Vec2::new(-8., 8.),
+            Body::Dynamic(Collider::from_circle(Vec2::ZERO, 2.)),
+        );
+
+    harness.step(1);
+    assert_near(harness.position("wall"), Vec2::ZERO);
+    assert_near(harness.position("box"), Vec2::new(12., 2.));
+    // Centered on the corner, so pushed out of the nearest edge.
+    assert_near(harness.position("ball"), Vec2::new(-10., 8.));
+
+    harness.step(4);
+    assert_near(harness.position("box"), Vec2::new(12., 2.));
+}
+
+#[test]
+fn dynamic_bodies_stop_at_static_bodies() {
+    let mut harness = Harness::new()
+        .with("wall", Vec2::new(64., 0.), Body::Static(square(16.)))
+        .with(
+            "ball",
+            Vec2::ZERO,
+            Body::Moving(Collider::from_circle(Vec2::ZERO, 4.), Vec2::new(128., 0.)),
+        );
+
+    harness.step(32);
+    assert_near(harness.position("ball"), Vec2::new(52., 0.));
+}
+
+#[test]
+fn triggers_fire_for_interacting_layers() {
+    let player = CollisionLayers::new(
+        CollisionLayers::DEFAULT | CollisionLayers::PLAYER,
+        CollisionLayers::ALL,
+    );
+
+    let mut harness = Harness::new()
+        .with("door", Vec2::ZERO, Body::Trigger(square(16.)))
+        .with_bundle("door", CollisionLayers::player_only())
+        .with("sensor", Vec2::ZERO, Body::Trigger(square(16.)))
+        .with("player", Vec2::new(4., 4.), Body::Dynamic(square(4.)))
+        .with_bundle("player", player)
+        .with("pot", Vec2::new(-4., 4.), Body::Dynamic(square(4.)))
+        .with("ghost", Vec2::new(-4., -4.), Body::Dynamic(square(4.)))
+        .with_bundle("ghost", CollisionLayers::none())
+        .with("outside", Vec2::new(40., 0.), Body::Dynamic(square(4.)));
+
+    harness.step(1);
+    assert!(harness.triggered("door", "player"));
+    assert!(!harness.triggered("door", "pot"));
+    assert!(!harness.triggered("door", "sensor"));
+    assert!(harness.triggered("sensor", "player"));
+    assert!(harness.triggered("sensor", "pot"));
+    assert!(!harness.triggered("sensor", "ghost"));
+    assert!(!harness.triggered("sensor", "outside"));
+}
+
+#[test]
+fn one_way_bodies_only_block_from_their_normal_side() {
+    let mut harness = Harness::new()
+        .with(
+            "ledge",
+            Vec2::ZERO,
+            Body::Static(Collider::from_rect(
+                Vec2::new(-32., -8.),
+                Vec2::new(64., 16.),
+            )),
+        )
+        .with_bundle("ledge", OneWay::new(Vec2::NEG_Y))
+        .with(
+            "climbing",
+            Vec2::new(-16., -14.),
+            Body::Moving(square(8.), Vec2::new(0., 64.)),
+        )
+        .with(
+            "hopping",
+            Vec2::new(16., 14.),
+            Body::Moving(square(8.), Vec2::new(0., -64.)),
+        );
+
+    harness.step(40);
+    assert_near(harness.position("climbing"), Vec2::new(-16., -12.));
+    assert_near(harness.position("hopping"), Vec2::new(16., -26.));
+}
+
+#[test]
+fn disabled_edges_do_not_push() {
+    let mut harness = Harness::new()
+        .with("left", Vec2::splat(8.), Body::Static(square(16.)))
+        .with_bundle(
+            "left",
+            CollisionEdges(CollisionEdges::ALL & !CollisionEdges::RIGHT),
+        )
+        .with("right", Vec2::new(24., 8.), Body::Static(square(16.)))
+        .with_bundle(
+            "right",
+            CollisionEdges(CollisionEdges::ALL & !CollisionEdges::LEFT),
+        )
+        // Overlaps the seam, less deeply than the top of the floor.
+        .with("box", Vec2::new(19., 17.), Body::Dynamic(square(8.)));
+
+    harness.step(1);
+    assert_near(harness.position("box"), Vec2::new(19., 20.));
+}
+
+#[test]
+fn collision_edges_from_directions() {
+    let top = CollisionEdges::facing(Vec2::Y);
+    assert_eq!(top, CollisionEdges(CollisionEdges::TOP));
+    assert!(top.allows(Vec2::new(0.5, 1.)));
+    assert!(!top.allows(Vec2::new(1., 0.5)));
+    assert!(!top.allows(Vec2::NEG_Y));
+    assert!(CollisionEdges(CollisionEdges::NONE).allows(Vec2::ZERO));
+}
+
+#[track_caller]
+fn assert_hit(hit: Option<SweepHit>, time: f32, normal: Vec2) {
+    let hit = hit.expect("expected a hit");
+    assert!(
+        (hit.time - time).abs() < 0.001 && hit.normal.abs_diff_eq(normal, 0.001),
+        "expected a hit at {time} facing {normal}, found {hit:?}"
+    );
+}
+
+#[test]
+fn ray_rect_hits_the_near_face() {
+    let (min, max) = (Vec2::new(5., -1.), Vec2::new(8., 1.));
+
+    assert_hit(
+        ray_rect(Vec2::ZERO, Vec2::new(10., 0.), min, max),
+        0.5,
+        Vec2::NEG_X,
+    );
+    assert_hit(
+        ray_rect(Vec2::new(6., 5.), Vec2::new(0., -8.), min, max),
+        0.5,
+        Vec2::Y,
+    );
+    // Falls short.
+    assert_eq!(ray_rect(Vec2::ZERO, Vec2::new(4., 0.), min, max), None);
+}
+
+#[test]
+fn ray_rect_ignores_grazes_and_starts_inside() {
+    let (min, max) = (Vec2::new(5., -1.), Vec2::new(8., 1.));
+
+    // Along the top edge.
+    assert_eq!(
+        ray_rect(Vec2::new(0., 1.), Vec2::new(10., 0.), min, max),
+        None
+    );
+    // Touching the corner only.
+    assert_eq!(
+        ray_rect(Vec2::new(4., 0.), Vec2::new(2., 2.), min, max),
+        None
+    );
+    assert_eq!(
+        ray_rect(Vec2::new(6., 0.), Vec2::new(10., 0.), min, max),
+        None
+    );
+}
+
+#[test]
+fn ray_circle_hits_and_grazes() {
+    let center = Vec2::new(8., 0.);
+
+    assert_hit(
+        ray_circle(Vec2::ZERO, Vec2::new(10., 0.), center, 2.),
+        0.6,
+        Vec2::NEG_X,
+    );
+    // A tangent touches, facing across the motion.
+    assert_hit(
+        ray_circle(Vec2::new(0., 2.), Vec2::new(10., 0.), center, 2.),
+        0.8,
+        Vec2::Y,
+    );
+    assert_eq!(
+        ray_circle(Vec2::new(0., 3.), Vec2::new(10., 0.), center, 2.),
+        None
+    );
+}
+
+#[test]
+fn ray_circle_ignores_starts_inside_and_moving_away() {
+    let center = Vec2::new(8., 0.);
+
+    assert_eq!(
+        ray_circle(Vec2::new(7., 0.), Vec2::new(10., 0.), center, 2.),
+        None
+    );
+    assert_eq!(
+        ray_circle(Vec2::ZERO, Vec2::new(-10., 0.), center, 2.),
+        None
+    );
+    assert_eq!(ray_circle(Vec2::ZERO, Vec2::ZERO, center, 2.), None);
+}
+
+#[test]
+fn rect_and_circle_sweeps() {
+    let wall = rect(Vec2::new(10., -2.), Vec2::new(4., 8.));
+
+    assert_hit(
+        rect(Vec2::ZERO, Vec2::splat(4.)).sweep(Vec2::new(20., 0.), &wall),
+        0.3,
+        Vec2::NEG_X,
+    );
+    assert_hit(
+        circle(Vec2::ZERO, 2.).sweep(Vec2::new(10., 0.), &circle(Vec2::new(10., 0.), 3.)),
+        0.5,
+        Vec2::NEG_X,
+    );
+    assert_hit(
+        rect(Vec2::new(0., -2.), Vec2::splat(4.))
+            .sweep(Vec2::new(20., 0.), &circle(Vec2::new(14., 0.), 2.)),
+        0.4,
+        Vec2::NEG_X,
+    );
+    assert_hit(
+        circle(Vec2::new(0., 2.), 2.).sweep(Vec2::new(20., 0.), &wall),
+        0.4,
+        Vec2::NEG_X,
+    );
+}
+
+#[test]
+fn circle_rect_sweep_grazes_and_starts_inside() {
+    let wall = rect(Vec2::ZERO, Vec2::splat(16.));
+
+    // Rolling along the top touches the corner, facing up so the motion slides on.
+    assert_hit(
+        circle(Vec2::new(-10., 20.), 4.).sweep(Vec2::new(30., 0.), &wall),
+        1. / 3.,
+        Vec2::Y,
+    );
+    assert_eq!(
+        circle(Vec2::new(14., 8.), 4.).sweep(Vec2::new(30., 0.), &wall),
+        None
+    );
+    assert_eq!(
+        rect(Vec2::new(14., 8.), Vec2::splat(4.)).sweep(Vec2::new(30., 0.), &wall),
+        None
+    );
+}
+
+#[test]
+fn continuous_collision_stops_fast_bodies() {
+    // Moves 40 pixels a tick, further than the wall and the bodies are thick.
+    let velocity = Vec2::new(40. * 64., 0.);
+    let mut harness = Harness::new()
+        .with(
+            "wall",
+            Vec2::new(62., 0.),
+            Body::Static(Collider::from_rect(
+                Vec2::new(-2., -16.),
+                Vec2::new(4., 32.),
+            )),
+        )
+        .with(
+            "swept",
+            Vec2::new(0., 8.),
+            Body::Moving(square(8.), velocity),
+        )
+        .with_bundle("swept", ContinuousCollision::default())
+        .with(
+            "tunnelling",
+            Vec2::new(0., -8.),
+            Body::Moving(square(8.), velocity),
+        );
+
+    harness.step(4);
+    assert!((harness.position("swept").x - 56.).abs() < 0.1);
+    assert!(harness.position("tunnelling").x > 64.);
+}
+
+#[test]
+fn continuous_collision_does_not_sweep_teleports() {
+    let mut harness = Harness::new()
+        .with(
+            "wall",
+            Vec2::new(32., 0.),
+            Body::Static(Collider::from_rect(
+                Vec2::new(-2., -16.),
+                Vec2::new(4., 32.),
+            )),
+        )
+        .with("swept", Vec2::ZERO, Body::Dynamic(square(8.)))
+        .with_bundle("swept", ContinuousCollision::default());
+    harness.step(1);
+
+    let swept = harness.entity("swept");
+    harness
+        .app
+        .world_mut()
+        .get_mut::<Transform>(swept)
+        .unwrap()
+        .translation = Vec3::new(64., 0., 0.);
+
+    harness.step(1);
+    assert_near(harness.position("swept"), Vec2::new(64., 0.));
+}
+
+/// Tile positions from rows of `#`, top row first.
+fn tiles(rows: &[&str], tile_size: f32) -> Vec<Vec2> {
+    rows.iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(x, _)| Vec2::new(x as f32, -(y as f32)) * tile_size)
+        })
+        .collect()
+}
+
+/// The area of the intersection of two rects.
+fn overlap(a: &RectCollider, b: &RectCollider) -> f32 {
+    let size = (a.br().min(b.br()) - a.tl.max(b.tl)).max(Vec2::ZERO);
+    size.x * size.y
+}
+
+/// Checks that merged colliders cover exactly the rects of the individual tiles.
+#[track_caller]
+fn assert_merged(rows: &[&str], expected: usize) {
+    let tile_size = 16.;
+    let positions = tiles(rows, tile_size);
+
+    // Each tile alone covers its position, extending down by a tile.
+    let singles = positions
+        .iter()
+        .map(|p| rect(Vec2::new(p.x, p.y - tile_size), Vec2::splat(tile_size)))
+        .collect::<Vec<_>>();
+    let merged = build_colliders_from_vec2(positions, tile_size)
+        .into_iter()
+        .map(|(position, collider)| match collider {
+            Collider::Rect(rect) => RectCollider {
+                tl: rect.tl + position,
+                ..rect
+            },
+            collider => panic!("expected a rect, found {collider:?}"),
+        })
+        .collect::<Vec<_>>();
+
+    let area = |rects: &[RectCollider]| rects.iter().map(|r| r.size.x * r.size.y).sum::<f32>();
+    assert_eq!(area(&merged), area(&singles), "{rows:?}");
+
+    for (i, a) in merged.iter().enumerate() {
+        for b in merged[i + 1..].iter() {
+            assert_eq!(overlap(a, b), 0., "{a:?} overlaps {b:?} in {rows:?}");
+        }
+    }
+
+    // Equal area without overlap, so covering every tile covers nothing else.
+    for single in singles.iter() {
+        let covered = merged.iter().map(|m| overlap(m, single)).sum::<f32>();
+        assert_eq!(covered, tile_size * tile_size, "{single:?} in {rows:?}");
+    }
+
+    assert_eq!(merged.len(), expected, "{rows:?}");
+}
+
+#[test]
+fn tile_colliders_merge_into_rectangles() {
+    assert_merged(&["#"], 1);
+    assert_merged(&["####", "####", "####"], 1);
+    assert_merged(&["#..", "#..", "###"], 2);
+    assert_merged(&[".#.", "###", ".#."], 3);
+    assert_merged(&["####", "#..#", "####"], 4);
+    assert_merged(&["#.#", ".#.", "#.#"], 5);
+    assert_merged(&["##..", "###.", "####"], 3);
+    assert_merged(&["...##", "..###", ".####", "#####", "..###"], 4);
+}
+
+#[test]
+fn tile_colliders_from_no_tiles() {
+    assert!(build_colliders_from_vec2(Vec::new(), 16.).is_empty());
+}
+
+#[test]
+fn stacks_of_dynamic_bodies_settle() {
+    let mut harness = Harness::new()
+        .with("floor", Vec2::ZERO, Body::Dynamic(square(8.)))
+        .with_bundle("floor", Massive)
+        .with("a", Vec2::new(0., 6.), Body::Dynamic(square(8.)))
+        .with("b", Vec2::new(0., 12.), Body::Dynamic(square(8.)))
+        .with("c", Vec2::new(0., 18.), Body::Dynamic(square(8.)))
+        .with("d", Vec2::new(0., 24.), Body::Dynamic(square(8.)));
+
+    harness.step(16);
+    assert_near(harness.position("floor"), Vec2::ZERO);
+    for (i, name) in ["a", "b", "c", "d"].into_iter().enumerate() {
+        assert_near(harness.position(name), Vec2::new(0., 8. * (i + 1) as f32));
+    }
+}
+
+#[test]
+fn dynamic_body_resolution_ignores_query_order() {
+    let stack = || {
+        Harness::new()
+            .with("a", Vec2::new(0., 0.), Body::Dynamic(square(8.)))
+            .with("b", Vec2::new(5., 2.), Body::Dynamic(square(8.)))
+            .with("c", Vec2::new(9., -3.), Body::Dynamic(square(8.)))
+            .with("d", Vec2::new(3., 6.), Body::Dynamic(square(8.)))
+    };
+
+    /// Moves a body to its own archetype, which the query visits last.
+    #[derive(Component)]
+    struct Reordered;
+
+    let mut harness = stack();
+    let mut reordered = stack()
+        .with_bundle("a", Reordered)
+        .with_bundle("c", Reordered);
+
+    harness.step(3);
+    reordered.step(3);
+    for name in ["a", "b", "c", "d"] {
+        assert_eq!(harness.position(name), reordered.position(name), "{name}");
+    }
+}
+
+#[test]
+fn dynamic_bodies_are_pushed_by_relative_mass() {
+    let mut harness = Harness::new()
+        .with("light", Vec2::ZERO, Body::Dynamic(square(8.)))
+        .with("heavy", Vec2::new(6., 0.), Body::Dynamic(square(8.)))
+        .with_bundle("heavy", Mass(3.))
+        .with("massive", Vec2::new(0., 40.), Body::Dynamic(square(8.)))
+        .with_bundle("massive", Massive)
+        .with("pushed", Vec2::new(6., 40.), Body::Dynamic(square(8.)))
+        .with_bundle("pushed", Mass(100.));
+
+    harness.step(1);
+    assert_near(harness.position("light"), Vec2::new(-1.5, 0.));
+    assert_near(harness.position("heavy"), Vec2::new(6.5, 0.));
+    assert_near(harness.position("massive"), Vec2::new(0., 40.));
+    assert_near(harness.position("pushed"), Vec2::new(8., 40.));
+}
+
+#[test]
+fn massive_bodies_do_not_resolve_each_other() {
+    let mut harness = Harness::new()
+        .with("a", Vec2::ZERO, Body::Dynamic(square(8.)))
+        .with_bundle("a", Massive)
+        .with("b", Vec2::new(6., 0.), Body::Dynamic(square(8.)))
+        .with_bundle("b", Massive);
+
+    harness.step(1);
+    assert_near(harness.position("a"), Vec2::ZERO);
+    assert_near(harness.position("b"), Vec2::new(6., 0.));
+    assert!(harness.contact("a", "b").is_some());
+}
+
+#[test]
+fn contacts_found_in_later_solver_iterations() {
+    // `b` only reaches `c` once `a` pushes it, after `b` and `c` were first checked.
+    let mut harness = Harness::new()
+        .with("c", Vec2::new(15.2, 0.), Body::Dynamic(square(8.)))
+        .with("b", Vec2::new(7., 0.), Body::Dynamic(square(8.)))
+        .with("a", Vec2::ZERO, Body::Dynamic(square(8.)));
+
+    harness.step(1);
+    assert!(harness.contact("a", "b").is_some());
+    let contact = harness.contact("b", "c").expect("expected a contact");
+    assert!(contact.penetration > 0.);
+    assert!(harness.contact("a", "c").is_none());
+}
+
+#[test]
+fn absolute_colliders_through_scaled_parents() {
+    let parent = Transform::from_xyz(10., 20., 0.).with_scale(Vec3::splat(2.));
+    let child = Transform::from_xyz(4., 0., 0.).with_scale(Vec3::new(1., 0.5, 1.));
+    let global = GlobalTransform::from(parent).mul_transform(child);
+
+    assert_eq!(
+        square(4.).absolute(&global),
+        AbsoluteCollider::Rect(rect(Vec2::new(14., 18.), Vec2::new(8., 4.)))
+    );
+    assert_eq!(
+        Collider::from_circle(Vec2::new(1., 0.), 3.).absolute(&global),
+        AbsoluteCollider::Circle(circle(Vec2::new(20., 20.), 6.))
+    );
+
+    // Mirroring keeps a rect with a positive size.
+    let mirrored =
+        GlobalTransform::from(parent).mul_transform(child.with_scale(Vec3::new(-1., 1., 1.)));
+    assert_eq!(
+        Collider::from_rect(Vec2::ZERO, Vec2::new(2., 1.)).absolute(&mirrored),
+        AbsoluteCollider::Rect(rect(Vec2::new(14., 20.), Vec2::new(4., 2.)))
+    );
+}
+
+#[test]
+fn absolute_colliders_through_rotated_parents() {
+    let quarter = GlobalTransform::from(Transform::from_rotation(Quat::from_rotation_z(
+        std::f32::consts::FRAC_PI_2,
+    )))
+    .mul_transform(Transform::from_xyz(4., 0., 0.));
+    match square(2.).absolute(&quarter) {
+        AbsoluteCollider::Rect(r) => {
+            assert_near(r.tl, Vec2::new(-1., 3.));
+            assert_near(r.size, Vec2::splat(2.));
+        }
+        other => panic!("expected a rect, found {other:?}"),
+    }
+
+    let eighth = GlobalTransform::from(Transform::from_rotation(Quat::from_rotation_z(
+        std::f32::consts::FRAC_PI_4,
+    )));
+    assert!(matches!(
+        square(2.).absolute(&eighth),
+        AbsoluteCollider::Polygon(_)
+    ));
+}
+
+#[test]
+fn parented_bodies_are_resolved_in_world_space() {
+    let mut harness = Harness::new()
+        .with("wall", Vec2::new(110., 0.), Body::Static(square(8.)))
+        .with("body", Vec2::ZERO, Body::Dynamic(square(8.)))
+        .with_parent(
+            "body",
+            Transform::from_xyz(100., 0., 0.).with_scale(Vec3::splat(2.)),
+        );
+
+    harness.step(1);
+    // The collider is 16 wide in world space and pushed back by 2, half that locally.
+    assert_near(harness.global_position("body"), Vec2::new(98., 0.));
+    assert_near(harness.position("body"), Vec2::new(-1., 0.));
+    assert!(harness.contact("body", "wall").is_some());
+}
+
+#[test]
+fn parented_one_way_bodies_use_the_world_space_start() {
+    let mut harness = Harness::new()
+        .with(
+            "ledge",
+            Vec2::ZERO,
+            Body::Static(Collider::from_rect(
+                Vec2::new(-32., -8.),
+                Vec2::new(64., 16.),
+            )),
+        )
+        .with_bundle("ledge", OneWay::new(Vec2::NEG_Y))
+        // Moves up in world space, from below the ledge.
+        .with(
+            "climbing",
+            Vec2::new(16., 14.),
+            Body::Moving(square(8.), Vec2::new(0., -64.)),
+        )
+        .with_parent(
+            "climbing",
+            Transform::from_rotation(Quat::from_rotation_z(std::f32::consts::PI)),
+        );
+
+    harness.step(40);
+    assert_near(harness.global_position("climbing"), Vec2::new(-16., -12.));
+}
+
+#[test]
+fn kinematic_controllers_slide_along_walls() {
+    let mut harness = Harness::new()
+        .with("wall", Vec2::new(16., 0.), Body::Static(square(16.)))
+        .with(
+            "player",
+            Vec2::new(3.5, 0.),
+            Body::Moving(square(8.), Vec2::new(64., 64.)),
+        )
+        .with_bundle("player", KinematicController::default());
+    let player = harness.entity("player");
+
+    harness.step(1);
+    let world = harness.app.world();
+    assert!(world.get::<KinematicController>(player).unwrap().blocked());
+    // The velocity into the wall is dropped, the rest is kept.
+    assert_near(world.get::<Velocity>(player).unwrap().0, Vec2::new(0., 64.));
+
+    let start = harness.position("player");
+    harness.step(4);
+    assert_near(harness.position("player"), start + Vec2::new(0., 4.));
+}
@@ -1,22 +1,22 @@
 use super::{
     collision::Collider,
     collision::CollidesWith,
+    collision::DynamicBody,
+    contact::{Contact, Contacts},
+    layers::CollisionLayers,
     spatial::{SpatialData, SpatialHash},
 };
 use bevy::{prelude::*, utils::hashbrown::HashMap};
 
 /// Marks an entity as a [`TriggerEvent`] source.
 ///
-/// Can exist in combination with a [`StaticBody`] or [`DynamicBody`].
+/// Can exist in combination with a [`StaticBody`](super::collision::StaticBody) or
+/// [`DynamicBody`]. Fires for other triggers and dynamic bodies whose [`CollisionLayers`]
+/// interact with its own.
 #[derive(Debug, Default, Clone, Copy, Component)]
-#[require(TriggerLayer)]
+#[require(CollisionLayers)]
 pub struct Trigger(pub Collider);
 
-/// Both the [`Trigger`] and the `flipper` need to have the same [`TriggerLayer`] for a
-/// [`TriggerEvent`] to be emitted.
-#[derive(Debug, Default, Clone, Copy, Component)]
-pub struct TriggerLayer(pub usize);
-
 /// In the case of trigger triggers trigger, two triggers will each trigger, both triggering the
 /// other trigger.
 #[derive(Debug, Clone, Copy, Event)]
@@ -25,90 +25,50 @@ pub struct TriggerEvent {
     pub target: Entity,
 }
 
-#[derive(Default, Resource)]
-pub struct TriggerLayerRegistry(Vec<usize>);
-
-pub fn register_trigger_layers(
-    layers: Query<&TriggerLayer, Added<TriggerLayer>>,
-    mut registry: ResMut<TriggerLayerRegistry>,
-) {
-    for layer in layers.iter() {
-        if !registry.0.contains(&layer.0) {
-            registry.0.push(layer.0);
-        }
-    }
-}
-
 pub fn handle_triggers(
-    triggers: Query<(Entity, &Transform, &Trigger, &TriggerLayer)>,
-    dynamic_bodies: Query<(Entity, &Transform, &Collider, &TriggerLayer)>,
-    layer_registry: Res<TriggerLayerRegistry>,
+    triggers: Query<(Entity, &GlobalTransform, &Trigger, &CollisionLayers)>,
+    dynamic_bodies: Query<
+        (Entity, &GlobalTransform, &Collider, &CollisionLayers),
+        With<DynamicBody>,
+    >,
     mut writer: EventWriter<TriggerEvent>,
+    mut contacts: ResMut<Contacts>,
 ) {
-    // TODO: write better code
-    let mut event_hash: HashMap<(Entity, Entity), ()> = HashMap::default();
+    let mut event_hash: HashMap<(Entity, Entity), Vec2> = HashMap::default();
 
-    for layer in layer_registry.0.iter() {
-        let layer_triggers = triggers
+    let trigger_map = SpatialHash::new_with(
+        64.,
+        triggers
             .iter()
-            .filter(|(_, _, _, l)| l.0 == *layer)
-            .collect::<Vec<_>>();
-
-        let trigger_map = SpatialHash::new_with(
-            64.,
-            layer_triggers
-                .iter()
-                .map(|(e, t, tg, _)| SpatialData::from_entity(*e, t, &tg.0, ())),
-        );
+            .map(|(e, t, tg, l)| SpatialData::from_entity(e, t, &tg.0, *l)),
+    );
 
-        for (entity, transform, trigger, _) in layer_triggers.iter() {
-            let collider = trigger.0.absolute(transform);
-
-            for SpatialData {
-                entity: e,
-                collider: c,
-                ..
-            } in trigger_map.nearby_objects(&collider.position())
-            {
-                if *e != *entity && collider.collides_with(c) {
-                    event_hash.insert((*entity, *e), ());
-                    //writer.send(TriggerEvent {
-                    //    trigger: *entity,
-                    //    target: *e,
-                    //});
-                }
-            }
-        }
-
-        let dynamic_body_map = SpatialHash::new_with(
-            64.,
-            dynamic_bodies
-                .iter()
-                .filter(|(_, _, _, l)| l.0 == *layer)
-                .map(|(e, t, c, _)| SpatialData::from_entity(e, t, c, ())),
-        );
+    let dynamic_body_map = SpatialHash::new_with(
+        64.,
+        dynamic_bodies
+            .iter()
+            .map(|(e, t, c, l)| SpatialData::from_entity(e, t, c, *l)),
+    );
 
-        for (entity, transform, trigger, _) in layer_triggers.iter() {
-            let collider = trigger.0.absolute(transform);
+    for (entity, transform, trigger, layers) in triggers.iter() {
+        let collider = trigger.0.absolute(transform);
 
-            for SpatialData {
-                entity: e,
-                collider: c,
-                ..
-            } in dynamic_body_map.nearby_objects(&collider.position())
-            {
-                if collider.collides_with(c) {
-                    event_hash.insert((*entity, *e), ());
-                    //writer.send(TriggerEvent {
-                    //    trigger: *entity,
-                    //    target: *e,
-                    //});
-                }
+        for SpatialData {
+            entity: e,
+            collider: c,
+            data: l,
+        } in trigger_map
+            .nearby_objects(&collider.position())
+            .chain(dynamic_body_map.nearby_objects(&collider.position()))
+        {
+            if *e != entity && layers.interacts_with(l) && collider.collides_with(c) {
+                event_hash.insert((entity, *e), collider.resolution(c));
             }
         }
     }
 
-    for (trigger, target) in event_hash.into_keys() {
+    for ((trigger, target), resolution) in event_hash.into_iter() {
         writer.send(TriggerEvent { trigger, target });
+        contacts.insert(Contact::from_resolution(trigger, target, resolution, true));
     }
 }
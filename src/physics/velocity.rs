@@ -1,9 +1,109 @@
+use super::collision::Mass;
+use super::controller::KinematicController;
+use super::interpolation::Position;
 use bevy::prelude::*;
 
-#[derive(Component)]
+#[derive(Default, Component)]
+#[require(Position)]
 pub struct Velocity(pub Vec2);
 
-pub fn apply_velocity(mut query: Query<(&mut Transform, &Velocity)>, time: Res<Time>) {
+/// Change in [`Velocity`] per second.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+#[require(Velocity)]
+pub struct Acceleration(pub Vec2);
+
+/// Fraction of [`Velocity`] lost per second, e.g. friction.
+///
+/// Slippery surfaces are a low damping, and eased stops come for free.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+#[require(Velocity)]
+pub struct LinearDamping(pub f32);
+
+/// Limits the length of [`Velocity`] after integration.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[require(Velocity)]
+pub struct MaxSpeed(pub f32);
+
+/// Forces and impulses applied with [`VelocityCommands`], cleared every physics tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+#[require(Velocity)]
+pub struct ExternalForces {
+    pub force: Vec2,
+    pub impulse: Vec2,
+}
+
+pub trait VelocityCommands {
+    /// Applies a force over the next physics tick, scaled by the inverse of the entity's
+    /// [`Mass`].
+    fn apply_force(&mut self, force: Vec2) -> &mut Self;
+
+    /// Instantly changes the entity's [`Velocity`], scaled by the inverse of its [`Mass`].
+    fn apply_impulse(&mut self, impulse: Vec2) -> &mut Self;
+}
+
+impl VelocityCommands for EntityCommands<'_> {
+    fn apply_force(&mut self, force: Vec2) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            entity.entry::<ExternalForces>().or_default().force += force;
+        })
+    }
+
+    fn apply_impulse(&mut self, impulse: Vec2) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            entity.entry::<ExternalForces>().or_default().impulse += impulse;
+        })
+    }
+}
+
+/// Integrates [`Acceleration`], [`ExternalForces`], [`LinearDamping`] and [`MaxSpeed`] into
+/// [`Velocity`].
+pub fn integrate_velocity(
+    mut query: Query<(
+        &mut Velocity,
+        Option<&Acceleration>,
+        Option<&mut ExternalForces>,
+        Option<&LinearDamping>,
+        Option<&MaxSpeed>,
+        Option<&Mass>,
+    )>,
+    time: Res<Time<Fixed>>,
+) {
+    let delta = time.delta_secs();
+
+    for (mut velocity, acceleration, forces, damping, max_speed, mass) in query.iter_mut() {
+        let mut new_velocity = velocity.0;
+        let inverse_mass = 1. / mass.map(|m| m.0).unwrap_or(1.).max(f32::EPSILON);
+
+        if let Some(acceleration) = acceleration {
+            new_velocity += acceleration.0 * delta;
+        }
+
+        if let Some(mut forces) = forces {
+            if *forces != ExternalForces::default() {
+                new_velocity += (forces.force * delta + forces.impulse) * inverse_mass;
+                *forces = ExternalForces::default();
+            }
+        }
+
+        if let Some(damping) = damping {
+            new_velocity /= 1. + damping.0 * delta;
+        }
+
+        if let Some(max_speed) = max_speed {
+            new_velocity = new_velocity.clamp_length_max(max_speed.0);
+        }
+
+        // Avoid triggering change detection for bodies at rest.
+        if new_velocity != velocity.0 {
+            velocity.0 = new_velocity;
+        }
+    }
+}
+
+pub fn apply_velocity(
+    mut query: Query<(&mut Transform, &Velocity), Without<KinematicController>>,
+    time: Res<Time<Fixed>>,
+) {
     for (mut transform, velocity) in query.iter_mut() {
         transform.translation += (velocity.0 * time.delta_secs()).extend(0.);
     }
@@ -0,0 +1,97 @@
+use crate::save::SaveCommands;
+use crate::scenes::park::ParkScene;
+use crate::scenes::{replace_active_scene, SceneId};
+#[cfg(debug_assertions)]
+use bevy::input::{keyboard::KeyboardInput, ButtonState};
+use bevy::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Resets the world to the moment of creation when Izzy dies.
+///
+/// Every scene is despawned and the park respawned. Resources registered with
+/// [`RegisterTransient::register_transient`] return to their defaults, while everything
+/// else, like the [`Inventory`](crate::inventory::Inventory), persists.
+pub struct WorldResetPlugin;
+
+impl Plugin for WorldResetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WorldReset>()
+            .insert_resource(WorldLoop::default())
+            .init_resource::<TransientState>()
+            .add_systems(PostUpdate, reset_world.run_if(on_event::<WorldReset>));
+
+        #[cfg(debug_assertions)]
+        app.add_systems(Update, reset_on_key);
+    }
+}
+
+/// Send to reset the world.
+#[derive(Debug, Default, Clone, Copy, Event)]
+pub struct WorldReset;
+
+/// How many times the world has reset.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct WorldLoop(pub u32);
+
+/// Whether the world has reset at least `count` times.
+pub fn looped(count: u32) -> impl Fn(Res<WorldLoop>) -> bool {
+    move |world_loop: Res<WorldLoop>| world_loop.0 >= count
+}
+
+/// Resets transient resources.
+#[derive(Default, Resource)]
+struct TransientState(Vec<Box<dyn Fn(&mut World) + Send + Sync>>);
+
+pub trait RegisterTransient {
+    /// Resets `R` to its default when the world resets.
+    fn register_transient<R: Resource + Default>(&mut self) -> &mut Self {
+        self.register_transient_with(|resource: &mut R| *resource = R::default())
+    }
+
+    /// Calls `reset` on `R` when the world resets, for resources that keep some state.
+    fn register_transient_with<R: Resource>(&mut self, reset: fn(&mut R)) -> &mut Self;
+}
+
+impl RegisterTransient for App {
+    fn register_transient_with<R: Resource>(&mut self, reset: fn(&mut R)) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<TransientState>()
+            .0
+            .push(Box::new(move |world| {
+                if let Some(mut resource) = world.get_resource_mut::<R>() {
+                    reset(&mut resource);
+                }
+            }));
+        self
+    }
+}
+
+/// Resets the world with `R`, standing in for Izzy's death.
+#[cfg(debug_assertions)]
+fn reset_on_key(mut input: EventReader<KeyboardInput>, mut writer: EventWriter<WorldReset>) {
+    if input
+        .read()
+        .any(|i| i.state == ButtonState::Pressed && i.key_code == KeyCode::KeyR)
+    {
+        writer.send(WorldReset);
+    }
+}
+
+fn reset_world(world: &mut World) {
+    world.resource_mut::<Events<WorldReset>>().clear();
+
+    world.resource_scope(|world, state: Mut<TransientState>| {
+        for reset in state.0.iter() {
+            reset(world);
+        }
+    });
+
+    world.resource_mut::<WorldLoop>().0 += 1;
+
+    match replace_active_scene(world, &SceneId::new(&ParkScene)) {
+        Ok(()) => world.commands().save_game(),
+        Err(e) => error!("failed to respawn the park: {e}"),
+    }
+}
@@ -0,0 +1,85 @@
+use super::*;
+use crate::flags::{FlagsPlugin, StoryFlags};
+use crate::inventory::{Inventory, InventoryPlugin, KeyItem};
+use crate::save::{CompletedFragments, SaveFile, SavePlugin};
+use crate::scenes::{ActiveScene, RegisterScene, Scene, SceneRoot, VisitedScenes};
+use bevy::input::InputPlugin;
+use serde::{Deserialize, Serialize};
+
+/// Stands in for [`ParkScene`], which needs its level to spawn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Park;
+
+impl Scene for Park {
+    const NAME: &'static str = "park";
+
+    fn spawn(&self, _: &mut EntityCommands) {}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Bedroom;
+
+impl Scene for Bedroom {
+    const NAME: &'static str = "bedroom";
+
+    fn spawn(&self, _: &mut EntityCommands) {}
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        InputPlugin,
+        SavePlugin,
+        FlagsPlugin,
+        InventoryPlugin,
+        WorldResetPlugin,
+    ))
+    .insert_resource(SaveFile(
+        std::env::temp_dir()
+            .join("annual-reset-test.sav")
+            .to_string_lossy()
+            .into(),
+    ))
+    .init_resource::<VisitedScenes>()
+    .register_transient::<VisitedScenes>()
+    .register_scene::<Park>()
+    .register_scene::<Bedroom>();
+    app.update();
+    app
+}
+
+fn reset(app: &mut App) {
+    app.world_mut().send_event(WorldReset);
+    app.update();
+}
+
+#[test]
+fn reset_respawns_the_park() {
+    let mut app = app();
+    let world = app.world_mut();
+    world.spawn(SceneRoot::new(Bedroom));
+    world.flush();
+    world
+        .resource_mut::<CompletedFragments>()
+        .0
+        .insert("broken_pot".into());
+    world.resource_mut::<Inventory>().0.insert(KeyItem::Pot);
+    world.resource_mut::<StoryFlags>().set("pot_broken", true);
+
+    reset(&mut app);
+
+    let world = app.world_mut();
+    let scenes = world
+        .query::<&ActiveScene>()
+        .iter(world)
+        .map(|scene| scene.0.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(scenes, [SceneId::new(&ParkScene)]);
+    assert!(world.resource::<CompletedFragments>().0.is_empty());
+    assert!(!world.resource::<VisitedScenes>().contains(&Bedroom));
+    assert!(world.resource::<VisitedScenes>().contains(&Park));
+    assert!(world.resource::<Inventory>().contains(KeyItem::Pot));
+    assert_eq!(*world.resource::<WorldLoop>(), WorldLoop(1));
+    assert!(!world.resource::<StoryFlags>().bool("pot_broken"));
+}
@@ -0,0 +1,231 @@
+use crate::flags::StoryFlags;
+use crate::inventory::{Inventory, KeyItem};
+use crate::player::{Direction, Player};
+use crate::reset::{RegisterTransient, WorldLoop};
+use crate::scenes::{
+    replace_active_scene, ActiveScene, Scene, SceneCommands, SceneId, SceneRoot, VisitedScenes,
+};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_sequence::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::ErrorKind;
+
+#[cfg(test)]
+mod tests;
+
+/// Bumped whenever the shape of [`SaveData`] changes.
+pub const SAVE_VERSION: u32 = 2;
+
+/// Saves progress whenever the scene, story flags or key items change, and restores it at
+/// startup.
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveFile::default())
+            .insert_resource(CompletedFragments::default())
+            .register_transient::<CompletedFragments>()
+            .add_systems(
+                PostUpdate,
+                save_progress.run_if(
+                    resource_exists_and_changed::<StoryFlags>
+                        .or(resource_exists_and_changed::<Inventory>),
+                ),
+            );
+    }
+}
+
+fn save_progress(mut commands: Commands) {
+    commands.save_game();
+}
+
+/// Where progress is saved.
+#[derive(Debug, Clone, Resource)]
+pub struct SaveFile(pub String);
+
+impl Default for SaveFile {
+    fn default() -> Self {
+        Self("annual.sav".into())
+    }
+}
+
+/// Keys of the [`SaveFragment::saved_once`] fragments that have completed, along with the
+/// trigger volumes that only fire once.
+#[derive(Debug, Default, Resource)]
+pub struct CompletedFragments(pub HashSet<String>);
+
+pub trait SaveFragment<D: Threaded, C: Threaded>: IntoFragment<D, C> + Sized {
+    /// Like `once`, but remembers that the fragment completed across saves.
+    ///
+    /// `key` must be unique among saved fragments.
+    fn saved_once(self, key: &'static str) -> impl IntoFragment<D, C> {
+        self.once()
+            .eval_id(
+                move |In(_): In<FragmentId>, completed: Res<CompletedFragments>| {
+                    !completed.0.contains(key)
+                },
+            )
+            .on_end(move |mut completed: ResMut<CompletedFragments>| {
+                completed.0.insert(key.into());
+            })
+    }
+}
+
+impl<D: Threaded, C: Threaded, T> SaveFragment<D, C> for T where T: IntoFragment<D, C> {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub position: [f32; 2],
+    pub direction: Direction,
+}
+
+/// Everything written to a save file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    pub scene: SceneId,
+    pub player: Option<PlayerState>,
+    pub completed: BTreeSet<String>,
+    pub visited: BTreeSet<SceneId>,
+    pub inventory: BTreeSet<KeyItem>,
+    pub world_loop: u32,
+    pub flags: StoryFlags,
+}
+
+impl SaveData {
+    /// Captures the current progress, or `None` if there is no active scene.
+    pub fn capture(world: &mut World) -> Option<Self> {
+        let scene = world.query::<&ActiveScene>().iter(world).next()?.0.clone();
+        let player = world
+            .query_filtered::<(&Transform, &Direction), With<Player>>()
+            .iter(world)
+            .next()
+            .map(|(transform, direction)| PlayerState {
+                position: transform.translation.xy().to_array(),
+                direction: *direction,
+            });
+
+        let completed = &world.resource::<CompletedFragments>().0;
+        let visited = &world.resource::<VisitedScenes>().0;
+        let inventory = &world.resource::<Inventory>().0;
+
+        Some(Self {
+            version: SAVE_VERSION,
+            scene,
+            player,
+            completed: completed.iter().cloned().collect(),
+            visited: visited.iter().cloned().collect(),
+            inventory: inventory.iter().copied().collect(),
+            world_loop: world.resource::<WorldLoop>().0,
+            flags: world.resource::<StoryFlags>().clone(),
+        })
+    }
+
+    /// Replaces the active scene and progress with the saved state.
+    pub fn restore(self, world: &mut World) -> Result<(), SaveError> {
+        if self.version != SAVE_VERSION {
+            return Err(SaveError::Version(self.version));
+        }
+
+        replace_active_scene(world, &self.scene).map_err(SaveError::Scene)?;
+
+        world.resource_mut::<CompletedFragments>().0 = self.completed.into_iter().collect();
+        world.resource_mut::<VisitedScenes>().0 = self.visited.into_iter().collect();
+        world.resource_mut::<Inventory>().0 = self.inventory.into_iter().collect();
+        world.resource_mut::<WorldLoop>().0 = self.world_loop;
+        world.insert_resource(self.flags);
+
+        // Spawns the scene, along with its player.
+        world.flush();
+
+        if let Some(state) = self.player {
+            let mut player =
+                world.query_filtered::<(&mut Transform, &mut Direction), With<Player>>();
+            if let Some((mut transform, mut direction)) = player.iter_mut(world).next() {
+                let z = transform.translation.z;
+                transform.translation = Vec2::from_array(state.position).extend(z);
+                *direction = state.direction;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_ron(&self) -> Result<String, SaveError> {
+        ron::ser::to_string_pretty(self, Default::default()).map_err(SaveError::Serialize)
+    }
+
+    pub fn from_ron(data: &str) -> Result<Self, SaveError> {
+        ron::from_str(data).map_err(SaveError::Deserialize)
+    }
+
+    pub fn read(path: &str) -> Result<Self, SaveError> {
+        Self::from_ron(&std::fs::read_to_string(path).map_err(SaveError::Io)?)
+    }
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Serialize(ron::Error),
+    Deserialize(ron::error::SpannedError),
+    Version(u32),
+    Scene(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Serialize(e) => write!(f, "{e}"),
+            Self::Deserialize(e) => write!(f, "{e}"),
+            Self::Version(version) => write!(
+                f,
+                "save version {version} does not match the current version {SAVE_VERSION}"
+            ),
+            Self::Scene(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+pub trait SaveCommands {
+    /// Writes the current progress to the [`SaveFile`].
+    fn save_game(&mut self);
+
+    /// Restores progress from the [`SaveFile`], or spawns `scene` if there is no save.
+    fn load_game_or<S: Scene>(&mut self, scene: S);
+}
+
+impl SaveCommands for Commands<'_, '_> {
+    fn save_game(&mut self) {
+        self.queue(|world: &mut World| {
+            let Some(data) = SaveData::capture(world) else {
+                return;
+            };
+
+            match data.to_ron() {
+                Ok(data) => {
+                    let path = world.resource::<SaveFile>().0.clone();
+                    world.commands().write_to_file(path, data.into_bytes());
+                }
+                Err(e) => error!("failed to save game: {e}"),
+            }
+        });
+    }
+
+    fn load_game_or<S: Scene>(&mut self, scene: S) {
+        self.queue(move |world: &mut World| {
+            let path = world.resource::<SaveFile>().0.clone();
+            if let Err(e) = SaveData::read(&path).and_then(|data| data.restore(world)) {
+                if !matches!(&e, SaveError::Io(e) if e.kind() == ErrorKind::NotFound) {
+                    error!("failed to load save: {e}");
+                }
+
+                world.spawn(SceneRoot::new(scene));
+            }
+        });
+    }
+}
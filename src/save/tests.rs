@@ -0,0 +1,144 @@
+use super::*;
+use crate::scenes::RegisterScene;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum TestScene {
+    Hall,
+    Attic,
+}
+
+impl Scene for TestScene {
+    const NAME: &'static str = "test";
+
+    fn spawn(&self, root: &mut EntityCommands) {
+        root.with_child((Player, Transform::from_xyz(8., 8., 1.)));
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SavePlugin))
+        .insert_resource(SaveFile(
+            std::env::temp_dir()
+                .join("annual-test.sav")
+                .to_string_lossy()
+                .into(),
+        ))
+        .init_resource::<VisitedScenes>()
+        .init_resource::<Inventory>()
+        .init_resource::<WorldLoop>()
+        .init_resource::<StoryFlags>()
+        .register_scene::<TestScene>();
+    app.update();
+    app
+}
+
+fn scene(app: &mut App) -> SceneId {
+    let world = app.world_mut();
+    world.query::<&ActiveScene>().single(world).0.clone()
+}
+
+#[test]
+fn save_round_trip() {
+    let mut app = app();
+    let world = app.world_mut();
+    let hall = world.spawn(SceneRoot::new(TestScene::Hall)).id();
+    world.flush();
+    world.entity_mut(hall).despawn_recursive();
+    world.spawn(SceneRoot::new(TestScene::Attic));
+    world.flush();
+    world
+        .resource_mut::<CompletedFragments>()
+        .0
+        .insert("cabinet".into());
+    world.resource_mut::<Inventory>().0.insert(KeyItem::Pot);
+    world.resource_mut::<WorldLoop>().0 = 2;
+    world.resource_mut::<StoryFlags>().set("petals", 4);
+
+    let mut player = world.query_filtered::<(&mut Transform, &mut Direction), With<Player>>();
+    for (mut transform, mut direction) in player.iter_mut(world) {
+        transform.translation = Vec3::new(-24., 40., 1.);
+        *direction = Direction::Left;
+    }
+
+    let data = SaveData::capture(world).unwrap();
+    let saved = data.to_ron().unwrap();
+    assert_eq!(SaveData::from_ron(&saved).unwrap(), data);
+
+    let mut loaded = self::app();
+    SaveData::from_ron(&saved)
+        .unwrap()
+        .restore(loaded.world_mut())
+        .unwrap();
+
+    assert_eq!(scene(&mut loaded), SceneId::new(&TestScene::Attic));
+    let world = loaded.world_mut();
+    let (transform, direction) = world
+        .query_filtered::<(&Transform, &Direction), With<Player>>()
+        .single(world);
+    assert_eq!(transform.translation, Vec3::new(-24., 40., 1.));
+    assert_eq!(*direction, Direction::Left);
+    assert!(world.resource::<CompletedFragments>().0.contains("cabinet"));
+    assert!(world.resource::<VisitedScenes>().contains(&TestScene::Hall));
+    assert!(world.resource::<Inventory>().contains(KeyItem::Pot));
+    assert_eq!(*world.resource::<WorldLoop>(), WorldLoop(2));
+    assert_eq!(world.resource::<StoryFlags>().int("petals"), 4);
+    assert_eq!(SaveData::capture(world).unwrap(), data);
+}
+
+#[test]
+fn restore_replaces_the_active_scene() {
+    let mut app = app();
+    app.world_mut().spawn(SceneRoot::new(TestScene::Hall));
+    app.world_mut().flush();
+
+    let mut data = SaveData::capture(app.world_mut()).unwrap();
+    data.scene = SceneId::new(&TestScene::Attic);
+    data.restore(app.world_mut()).unwrap();
+
+    assert_eq!(scene(&mut app), SceneId::new(&TestScene::Attic));
+    let world = app.world_mut();
+    assert_eq!(world.query::<&Player>().iter(world).count(), 1);
+}
+
+#[test]
+fn restore_rejects_other_versions() {
+    let mut app = app();
+    app.world_mut().spawn(SceneRoot::new(TestScene::Hall));
+    app.world_mut().flush();
+
+    let mut data = SaveData::capture(app.world_mut()).unwrap();
+    data.version += 1;
+    data.scene = SceneId::new(&TestScene::Attic);
+
+    assert!(matches!(
+        data.restore(app.world_mut()),
+        Err(SaveError::Version(_))
+    ));
+    assert_eq!(scene(&mut app), SceneId::new(&TestScene::Hall));
+}
+
+#[test]
+fn flag_changes_are_saved() {
+    let path = std::env::temp_dir().join("annual-flags-test.sav");
+    let path = path.to_string_lossy().to_string();
+    let _ = std::fs::remove_file(&path);
+
+    let mut app = app();
+    app.insert_resource(SaveFile(path.clone()));
+    app.world_mut().spawn(SceneRoot::new(TestScene::Hall));
+    app.world_mut()
+        .resource_mut::<StoryFlags>()
+        .set("door", true);
+    app.update();
+
+    // The save is written on the IO task pool.
+    let data = (0..100)
+        .find_map(|_| {
+            std::thread::sleep(Duration::from_millis(10));
+            SaveData::read(&path).ok()
+        })
+        .expect("the flag change should be saved");
+    assert!(data.flags.bool("door"));
+}
@@ -1,14 +1,17 @@
-use super::park::ParkScene;
-use super::{Scene, SceneTransition};
+use super::{RegisterScene, Scene, SceneTransition};
 use crate::annual::{self, Interactions};
+use crate::characters::*;
 use crate::color::srgb_from_hex;
 use crate::cutscene::CutsceneFragment;
+use crate::flags::FlagFragment;
 use crate::frag_util::FragExt;
 use crate::gfx::post_processing::PostProcessCommand;
-use crate::interactions::BindInteraction;
+use crate::gfx::transition::{TransitionCommands, TransitionEffect};
+use crate::interactions::{has_item, BindInteraction};
+use crate::inventory::{InventoryFragment, KeyItem};
+use crate::save::SaveFragment;
 use crate::textbox::frags::{textbox_once, EmptyCutscene};
 use crate::textbox::prelude::*;
-use crate::{characters::*, HEIGHT, WIDTH};
 use bevy::audio::Volume;
 use bevy::prelude::*;
 use bevy_light_2d::light::AmbientLight2d;
@@ -17,6 +20,7 @@ use bevy_seedling::sample::SamplePlayer;
 use bevy_seedling::{ConnectNode, VolumeNode};
 use bevy_sequence::combinators::delay::run_after;
 use bevy_sequence::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 pub struct HomePlugin;
@@ -28,21 +32,20 @@ pub struct HomePlugin;
 
 impl Plugin for HomePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            PreUpdate,
-            super::scene_transition::<BedroomScene, LivingRoomScene>
-                .run_if(super::scene_type_exists::<BedroomScene>),
-        );
+        app.register_scene::<BedroomScene>()
+            .register_scene::<LivingRoomScene>();
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BedroomScene {
     Test,
     PotBreak,
 }
 
 impl Scene for BedroomScene {
+    const NAME: &'static str = "bedroom";
+
     fn spawn(&self, root: &mut EntityCommands) {
         let id = root.id();
         match self {
@@ -60,7 +63,8 @@ pub fn init_test(entity: Entity) -> impl FnOnce(&mut World) {
 
         cabinet().spawn_box(&mut world.commands());
         spawn_root(
-            SceneTransition::new(BedroomScene::Test, ParkScene)
+            SceneTransition::named("park")
+                .with_effect(TransitionEffect::Dissolve(Color::BLACK))
                 .always()
                 .interaction(Interactions::BedroomDoor),
             &mut world.commands(),
@@ -68,6 +72,9 @@ pub fn init_test(entity: Entity) -> impl FnOnce(&mut World) {
     }
 }
 
+/// Set once Izzy wakes up to the pot breaking.
+const POT_BROKEN: &str = "pot_broken";
+
 const TRANSFORM: Transform = Transform::from_xyz(175., 175., -10.).with_scale(Vec3::splat(1. / 3.));
 
 fn cabinet() -> impl IntoBox {
@@ -80,7 +87,7 @@ fn cabinet() -> impl IntoBox {
     )
         .portrait_transform(TRANSFORM)
         .lock(Izzy)
-        .once()
+        .saved_once("cabinet")
         .always()
 }
 
@@ -102,21 +109,9 @@ pub fn init_pot_break(entity: Entity) -> impl FnOnce(&mut World) {
             ..Default::default()
         });
 
-        let transform = world
-            .query_filtered::<&Transform, With<annual::Player>>()
-            .single(world)
-            .clone();
-        let black = world
+        world
             .commands()
-            .spawn((
-                Sprite {
-                    color: Color::BLACK,
-                    custom_size: Some(Vec2::new(WIDTH, HEIGHT)),
-                    ..Default::default()
-                },
-                transform.with_translation(transform.translation.xy().extend(999.)),
-            ))
-            .id();
+            .cover_screen(TransitionEffect::Fade(Color::BLACK));
 
         run_after(
             Duration::from_secs_f32(0.5),
@@ -134,9 +129,9 @@ pub fn init_pot_break(entity: Entity) -> impl FnOnce(&mut World) {
                     Duration::from_secs_f32(2.5),
                     move |mut commands: Commands| {
                         textbox_once::<()>(
-                            s!("<0.2>...<1>[0.5]!").on_end(move |mut commands: Commands| {
-                                commands.entity(black).despawn()
-                            }),
+                            s!("<0.2>...<1>[0.5]!")
+                                .on_end(|mut commands: Commands| commands.reveal_screen())
+                                .set_flag(POT_BROKEN, true),
                             &mut commands,
                         );
                     },
@@ -147,7 +142,8 @@ pub fn init_pot_break(entity: Entity) -> impl FnOnce(&mut World) {
         );
 
         spawn_root(
-            SceneTransition::new(BedroomScene::PotBreak, LivingRoomScene::PotBreak)
+            SceneTransition::new(LivingRoomScene::PotBreak)
+                .with_effect(TransitionEffect::Iris(Color::BLACK))
                 .sound_with("sounds/music/home.wav", PlaybackSettings::LOOP)
                 .always()
                 .interaction(Interactions::BedroomDoor),
@@ -156,12 +152,14 @@ pub fn init_pot_break(entity: Entity) -> impl FnOnce(&mut World) {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LivingRoomScene {
     PotBreak,
 }
 
 impl Scene for LivingRoomScene {
+    const NAME: &'static str = "living_room";
+
     fn spawn(&self, root: &mut EntityCommands) {
         let id = root.id();
         match self {
@@ -194,13 +192,14 @@ pub fn init_living_room_pot_break(entity: Entity) -> impl FnOnce(&mut World) {
 
         (
             s!("Mr. `Flower|blue`?")
-                .interaction(Interactions::BrokenPot)
+                .interaction_when(Interactions::BrokenPot, not(has_item(KeyItem::Pot)))
                 .izzy(),
             s!("I'm sorry, `Izzy|green`... [1.5] Your pot is broken.").flower(),
         )
             .portrait_transform(TRANSFORM)
             .lock(Izzy)
-            .once()
+            .give_item(KeyItem::Pot)
+            .saved_once("broken_pot")
             .always()
             .spawn_box_with(&mut world.commands(), EmptyCutscene);
     }
@@ -1,14 +1,20 @@
+use crate::gfx::transition::{TransitionCommands, TransitionEffect};
+use crate::interactions::BindInteraction;
+use crate::reset::RegisterTransient;
+use crate::save::SaveCommands;
 use bevy::ecs::component::StorageType;
 use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
-use bevy::utils::HashSet;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::tasks::IoTaskPool;
+use bevy::utils::{HashMap, HashSet};
 use bevy_sequence::fragment::DataLeaf;
 use bevy_sequence::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::any::TypeId;
-//use bevy::ecs::world::DeferredWorld;
-//use bevy::tasks::IoTaskPool;
-//use std::fs::File;
-//use std::io::Write;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs::File, io::Write};
 
 mod emitters;
 pub mod home;
@@ -16,6 +22,9 @@ pub mod park;
 mod point_light;
 pub mod sandbox;
 
+#[cfg(test)]
+mod tests;
+
 pub struct ScenePlugin;
 
 impl Plugin for ScenePlugin {
@@ -27,6 +36,10 @@ impl Plugin for ScenePlugin {
             sandbox::SandboxPlugin,
         ))
         .insert_resource(SceneSystemCache::default())
+        .insert_resource(VisitedScenes::default())
+        .init_resource::<DoorFragments>()
+        .register_transient::<VisitedScenes>()
+        .add_systems(PreUpdate, scene_transition)
         .add_systems(
             Update,
             (
@@ -34,75 +47,137 @@ impl Plugin for ScenePlugin {
                 point_light::init_point_light_entities,
                 point_light::init_occluders,
                 emitters::leaf_emitters,
+                (spawn_doors, despawn_door_fragments),
             ),
         );
     }
 }
 
-pub trait Scene: 'static + Send + Sync + Clone + PartialEq {
-    //const SAVE_PATH: &'static str;
+pub trait Scene: 'static + Send + Sync + Clone + PartialEq + Serialize + DeserializeOwned {
+    /// Identifies the scene type in save files.
+    const NAME: &'static str;
 
     fn spawn(&self, root: &mut EntityCommands);
-    //fn save(_world: &mut DeferredWorld, _root: Entity) -> Option<Vec<u8>> {
-    //    None
-    //}
 }
 
-pub struct SceneTransition<F, T> {
-    from: F,
-    to: T,
+/// A serialized [`Scene`], comparable across scene types.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SceneId {
+    pub name: String,
+    pub data: String,
 }
 
-impl<F, T> SceneTransition<F, T> {
-    pub fn new(from: F, to: T) -> Self {
-        Self { from, to }
+impl SceneId {
+    pub fn new<S: Scene>(scene: &S) -> Self {
+        Self {
+            name: S::NAME.into(),
+            data: ron::to_string(scene).expect("scenes should serialize"),
+        }
     }
-}
 
-impl<F, T> Default for SceneTransition<F, T>
-where
-    F: Default,
-    T: Default,
-{
-    fn default() -> Self {
+    /// Parses a `name:data` address, where `data` is the scene in RON.
+    ///
+    /// The data can be left out for unit scenes, so `park` is the same as `park:()`.
+    pub fn parse(address: &str) -> Self {
+        let (name, data) = address.split_once(':').unwrap_or((address, "()"));
+
         Self {
-            from: F::default(),
-            to: T::default(),
+            name: name.trim().into(),
+            data: data.trim().into(),
         }
     }
 }
 
-impl<F, T> Clone for SceneTransition<F, T>
-where
-    F: Scene,
-    T: Scene,
-{
-    fn clone(&self) -> Self {
+/// Inserted on every [`SceneRoot`] so the active scene can be found without knowing its type.
+#[derive(Debug, Component)]
+pub struct ActiveScene(pub SceneId);
+
+/// Spawns scenes from their [`SceneId`].
+#[derive(Default, Resource)]
+pub struct SceneRegistry(HashMap<&'static str, SpawnScene>);
+
+type SpawnScene = fn(&str, &mut Commands) -> Result<(), ron::error::SpannedError>;
+
+impl SceneRegistry {
+    /// Queues the scene's [`SceneRoot`] spawn, failing if `id` is unknown or malformed.
+    pub fn spawn(&self, id: &SceneId, commands: &mut Commands) -> Result<(), String> {
+        let spawn = self
+            .0
+            .get(id.name.as_str())
+            .ok_or_else(|| format!("unknown scene `{}`", id.name))?;
+        spawn(&id.data, commands).map_err(|e| format!("invalid scene `{}`: {e}", id.name))
+    }
+}
+
+fn spawn_scene<S: Scene>(
+    data: &str,
+    commands: &mut Commands,
+) -> Result<(), ron::error::SpannedError> {
+    let scene: S = ron::from_str(data)?;
+    commands.spawn(SceneRoot::new(scene));
+    Ok(())
+}
+
+pub trait RegisterScene {
+    fn register_scene<S: Scene>(&mut self) -> &mut Self;
+}
+
+impl RegisterScene for App {
+    fn register_scene<S: Scene>(&mut self) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<SceneRegistry>()
+            .0
+            .insert(S::NAME, spawn_scene::<S>);
+        self
+    }
+}
+
+/// Switches from the active scene to `to`.
+///
+/// The old scene stays until the screen is covered, and players are locked until the new
+/// scene is revealed.
+#[derive(Debug, Clone)]
+pub struct SceneTransition {
+    to: SceneId,
+    effect: TransitionEffect,
+}
+
+impl SceneTransition {
+    pub fn new(to: impl Scene) -> Self {
+        Self {
+            to: SceneId::new(&to),
+            effect: TransitionEffect::default(),
+        }
+    }
+
+    /// Transitions to a scene address, like `living_room:PotBreak`.
+    ///
+    /// See [`SceneId::parse`].
+    pub fn named(address: &str) -> Self {
         Self {
-            from: self.from.clone(),
-            to: self.to.clone(),
+            to: SceneId::parse(address),
+            effect: TransitionEffect::default(),
         }
     }
+
+    pub fn with_effect(mut self, effect: TransitionEffect) -> Self {
+        self.effect = effect;
+        self
+    }
 }
 
-pub fn scene_transition<F: Scene, S: Scene>(
+fn scene_transition(
     mut commands: Commands,
-    mut reader: EventReader<FragmentEvent<SceneTransition<F, S>>>,
-    from: Option<Single<Entity, With<SceneRoot<F>>>>,
+    mut reader: EventReader<FragmentEvent<SceneTransition>>,
 ) {
     for event in reader.read() {
-        if let Some(ref from) = from {
-            commands.entity(**from).despawn_recursive();
-            commands.spawn(SceneRoot::new(event.data.to.clone()));
-        }
+        commands.transition_to(event.data.to.clone(), event.data.effect);
     }
 }
 
-impl<F: Scene + Clone, S: Scene + Clone> IntoFragment<SceneTransition<F, S>, ()>
-    for SceneTransition<F, S>
-{
+impl IntoFragment<SceneTransition, ()> for SceneTransition {
     fn into_fragment(self, context: &Context<()>, commands: &mut Commands) -> FragmentId {
-        <_ as IntoFragment<SceneTransition<F, S>, ()>>::into_fragment(
+        <_ as IntoFragment<SceneTransition, ()>>::into_fragment(
             DataLeaf::new(self),
             context,
             commands,
@@ -110,6 +185,68 @@ impl<F: Scene + Clone, S: Scene + Clone> IntoFragment<SceneTransition<F, S>, ()>
     }
 }
 
+/// The transition fragment spawned for each door, despawned along with the door.
+#[derive(Default, Resource)]
+struct DoorFragments(HashMap<Entity, Entity>);
+
+/// Binds a [`SceneTransition`] to every interaction with a `to` address, so doors can be
+/// placed in LDtk alone.
+fn spawn_doors(
+    mut commands: Commands,
+    doors: Query<(Entity, &crate::annual::Interaction), Added<crate::annual::Interaction>>,
+) {
+    for (door, interaction) in doors.iter().filter(|(_, door)| !door.to.is_empty()) {
+        spawn_root(
+            SceneTransition::named(&interaction.to)
+                .always()
+                .interaction(interaction.interactions)
+                .eval_id(
+                    move |In(id): In<FragmentId>,
+                          doors: Query<(), With<crate::annual::Interaction>>,
+                          mut fragments: ResMut<DoorFragments>| {
+                        let exists = doors.contains(door);
+                        if exists {
+                            fragments.0.entry(door).or_insert(id.entity());
+                        }
+                        exists
+                    },
+                ),
+            &mut commands,
+        );
+    }
+}
+
+fn despawn_door_fragments(
+    mut commands: Commands,
+    mut removed: RemovedComponents<crate::annual::Interaction>,
+    mut fragments: ResMut<DoorFragments>,
+) {
+    for door in removed.read() {
+        if let Some(fragment) = fragments.0.remove(&door) {
+            commands.entity(fragment).despawn_recursive();
+        }
+    }
+}
+
+/// Queues the spawn of `id` and despawns the active scene roots.
+///
+/// Nothing changes if `id` cannot be spawned.
+pub fn replace_active_scene(world: &mut World, id: &SceneId) -> Result<(), String> {
+    world.resource_scope(|world, registry: Mut<SceneRegistry>| {
+        registry.spawn(id, &mut world.commands())
+    })?;
+
+    let roots = world
+        .query_filtered::<Entity, With<ActiveScene>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    for root in roots {
+        world.entity_mut(root).despawn_recursive();
+    }
+
+    Ok(())
+}
+
 pub struct SceneRoot<S: Scene>(S);
 
 impl<S: Scene> SceneRoot<S> {
@@ -122,18 +259,16 @@ impl<S: Scene> Component for SceneRoot<S> {
     const STORAGE_TYPE: StorageType = StorageType::Table;
 
     fn register_component_hooks(hooks: &mut bevy::ecs::component::ComponentHooks) {
-        hooks
-            .on_add(|mut world, entity, _| {
-                let scene = world.get::<SceneRoot<S>>(entity).unwrap();
-                scene.0.clone().spawn(&mut world.commands().entity(entity));
-            })
-            .on_remove(|_world, _entity, _| {
-                //if let Some(save) = S::save(&mut world, entity) {
-                //    world
-                //        .commands()
-                //        .write_to_file(S::SAVE_PATH, save);
-                //}
-            });
+        hooks.on_add(|mut world, entity, _| {
+            let scene = world.get::<SceneRoot<S>>(entity).unwrap().0.clone();
+            let id = SceneId::new(&scene);
+            world.resource_mut::<VisitedScenes>().0.insert(id.clone());
+
+            let mut commands = world.commands();
+            let mut root = commands.entity(entity);
+            root.insert(ActiveScene(id));
+            scene.spawn(&mut root);
+        });
     }
 }
 
@@ -143,7 +278,14 @@ pub trait SceneCommands {
         S: Scene,
         C: IntoSystemConfigs<M> + Send + 'static;
 
-    //fn write_to_file(&self, path: impl Into<String>, data: Vec<u8>);
+    /// Switches to the scene at `address`, like `bedroom:PotBreak`.
+    fn switch_scene(&mut self, address: &str) {
+        self.switch_scene_to(SceneId::parse(address));
+    }
+
+    fn switch_scene_to(&mut self, id: SceneId);
+
+    fn write_to_file(&self, path: impl Into<String>, data: Vec<u8>);
 }
 
 impl SceneCommands for Commands<'_, '_> {
@@ -162,23 +304,52 @@ impl SceneCommands for Commands<'_, '_> {
         });
     }
 
-    //fn write_to_file(&self, path: impl Into<String>, data: Vec<u8>) {
-    //    let path = path.into();
-    //
-    //    #[cfg(not(target_arch = "wasm32"))]
-    //    IoTaskPool::get()
-    //        .spawn(async move {
-    //            if let Err(e) = File::create(&path).and_then(|mut file| file.write(&data)) {
-    //                error!("failed to write to file {:?}: {e}", &path);
-    //            }
-    //        })
-    //        .detach();
-    //}
+    fn switch_scene_to(&mut self, id: SceneId) {
+        self.queue(
+            move |world: &mut World| match replace_active_scene(world, &id) {
+                Ok(()) => world.commands().save_game(),
+                Err(e) => error!("failed to switch scene: {e}"),
+            },
+        );
+    }
+
+    fn write_to_file(&self, path: impl Into<String>, data: Vec<u8>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = path.into();
+            IoTaskPool::get()
+                .spawn(async move {
+                    if let Err(e) = File::create(&path).and_then(|mut file| file.write_all(&data)) {
+                        error!("failed to write to file {:?}: {e}", &path);
+                    }
+                })
+                .detach();
+        }
+
+        // There is no file system to save to on the web.
+        #[cfg(target_arch = "wasm32")]
+        let _ = (path, data);
+    }
 }
 
 #[derive(Default, Resource)]
 struct SceneSystemCache(HashSet<TypeId>);
 
+/// Every scene that has been spawned.
+#[derive(Debug, Default, Resource)]
+pub struct VisitedScenes(pub HashSet<SceneId>);
+
+impl VisitedScenes {
+    pub fn contains<S: Scene>(&self, scene: &S) -> bool {
+        self.0.contains(&SceneId::new(scene))
+    }
+}
+
+/// Whether `scene` has ever been spawned.
+pub fn visited<S: Scene>(scene: S) -> impl Fn(Res<VisitedScenes>) -> bool {
+    move |visited: Res<VisitedScenes>| visited.contains(&scene)
+}
+
 fn scene_exists<S: Scene>(scene: S) -> impl Fn(Option<Single<&SceneRoot<S>>>) -> bool {
     move |scene_query: Option<Single<&SceneRoot<S>>>| {
         scene_query.is_some_and(|s| s.into_inner().0 == scene)
@@ -4,8 +4,12 @@ use crate::scenes::{Scene, SceneRoot};
 use bevy::prelude::*;
 use bevy_light_2d::prelude::*;
 use rand::Rng;
+use std::f32::consts::TAU;
 
 const SPEED: f32 = 20.;
+const WANDER_ACCELERATION: f32 = 30.;
+/// Maximum change in wander direction per second, in radians.
+const WANDER_TURN: f32 = 6.;
 
 #[derive(Default, Component)]
 #[require(Transform, Visibility, FireflySpawnerState)]
@@ -24,6 +28,7 @@ pub struct FireflySpawnerState {
 pub struct FireflyParent(Entity);
 
 #[derive(Default, Component)]
+#[require(MaxSpeed(|| MaxSpeed(SPEED)))]
 pub struct Firefly;
 
 #[derive(Component)]
@@ -59,6 +64,10 @@ pub fn spawn_fireflies<S: Scene>(
                             rand::thread_rng().gen_range(-1.0..1.0) * SPEED,
                             rand::thread_rng().gen_range(-1.0..1.0) * SPEED,
                         )),
+                        Acceleration(
+                            Vec2::from_angle(rand::thread_rng().gen_range(0.0..TAU))
+                                * WANDER_ACCELERATION,
+                        ),
                         Lifetime {
                             timer: Timer::from_seconds(spawner.lifetime, TimerMode::Once),
                             light_intensity_curve: UnevenSampleAutoCurve::new([
@@ -91,6 +100,16 @@ pub fn spawn_fireflies<S: Scene>(
     }
 }
 
+/// Slowly turns each firefly's [`Acceleration`] so they drift around.
+pub fn wander(mut fireflies: Query<&mut Acceleration, With<Firefly>>, time: Res<Time>) {
+    let max_turn = WANDER_TURN * time.delta_secs();
+
+    for mut acceleration in fireflies.iter_mut() {
+        let turn = rand::thread_rng().gen_range(-max_turn..=max_turn);
+        acceleration.0 = Vec2::from_angle(turn).rotate(acceleration.0);
+    }
+}
+
 pub fn update_lifetime(
     mut commands: Commands,
     mut spawner_query: Query<&mut FireflySpawnerState>,
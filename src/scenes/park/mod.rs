@@ -1,14 +1,15 @@
 use self::fireflies::FireflySpawner;
 use self::player::Player;
-use super::Scene;
-use crate::annual::{self, Interactions};
+use super::{RegisterScene, Scene};
+use crate::annual::{self, Interactions, TriggerVolumes};
 use crate::cutscene::CutsceneFragment;
 use crate::frag_util::FragExt;
 use crate::gfx::camera::CameraCurveFragment;
 use crate::gfx::post_processing::PostProcessCommand;
 use crate::gfx::zorder::YOrigin;
-use crate::interactions::BindInteraction;
+use crate::interactions::{BindInteraction, BindTriggerVolume};
 use crate::physics::prelude::*;
+use crate::save::SaveFragment;
 use crate::textbox::prelude::*;
 use crate::{characters::*, TILE_SIZE};
 use bevy::core_pipeline::bloom::Bloom;
@@ -20,6 +21,7 @@ use bevy_pretty_text::prelude::*;
 use bevy_seedling::sample::SamplePlayer;
 use bevy_seedling::RepeatMode;
 use bevy_sequence::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 mod fireflies;
@@ -31,7 +33,7 @@ struct ParkTreeComponents1;
 
 #[derive(Default, Component)]
 #[require(YOrigin(|| YOrigin(-TILE_SIZE * 4.25)), StaticBody)]
-#[require(Collider(|| Collider::from_rect(Vec2::new(TILE_SIZE * 1.75, -TILE_SIZE * 4.25), Vec2::new(28., 8.))))]
+#[require(Collider(|| Collider::from_capsule(Vec2::new(TILE_SIZE * 2., -TILE_SIZE * 4.), Vec2::new(TILE_SIZE * 3.25, -TILE_SIZE * 4.), 4.)))]
 struct ParkTreeComponents2;
 
 #[derive(Default, Component)]
@@ -41,7 +43,7 @@ struct LampComponents;
 
 #[derive(Default, Component)]
 #[require(YOrigin(|| YOrigin(-TILE_SIZE * 2.)), StaticBody)]
-#[require(Collider(|| Collider::from_rect(Vec2::new(3., -32.), Vec2::new(27., 8.))))]
+#[require(Collider(|| Collider::from_capsule(Vec2::new(7., -28.), Vec2::new(26., -28.), 4.)))]
 struct BenchComponents;
 
 #[derive(Default, Component)]
@@ -69,9 +71,11 @@ impl Plugin for ParkPlugin {
                 scene,
                 fireflies::spawn_fireflies::<ParkScene>,
                 fireflies::update_lifetime,
+                fireflies::wander,
             )
                 .run_if(super::scene_type_exists::<ParkScene>),
         )
+        .register_scene::<ParkScene>()
         .register_required_components::<annual::ParkTree1, ParkTreeComponents1>()
         .register_required_components::<annual::ParkTree2, ParkTreeComponents1>()
         .register_required_components::<annual::ParkTree3, ParkTreeComponents2>()
@@ -86,10 +90,12 @@ impl Plugin for ParkPlugin {
     }
 }
 
-#[derive(Default, Clone, PartialEq)]
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParkScene;
 
 impl Scene for ParkScene {
+    const NAME: &'static str = "park";
+
     fn spawn(&self, root: &mut EntityCommands) {
         let id = root.id();
         let mut commands = root.commands();
@@ -126,9 +132,14 @@ pub fn init(entity: Entity) -> impl FnOnce(&mut World) {
             s!("Oh, `Mr. Tree|green`,[0.25] you are so very big!").textbox(),
             "Do you have any pretty birds?".textbox(),
         )
-            .once()
+            .saved_once("large_tree")
             .interaction(Interactions::LargeTree)
             .spawn_box_with(&mut world.commands(), ());
+
+        s!("It's so dark out here...[0.5] I shouldn't wander any further.")
+            .on_enter(TriggerVolumes::Fringes)
+            .izzy()
+            .spawn_box(&mut world.commands());
     }
 }
 
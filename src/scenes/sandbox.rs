@@ -1,4 +1,4 @@
-use super::Scene;
+use super::{RegisterScene, Scene};
 use crate::gfx::post_processing::PostProcessCommand;
 use crate::textbox::frags::IntoBox;
 use crate::{annual, IntoFlower, IntoIzzy};
@@ -9,11 +9,13 @@ use bevy_pretty_text::prelude::*;
 use bevy_seedling::sample::SamplePlayer;
 use bevy_seedling::RepeatMode;
 use bevy_sequence::prelude::FragmentExt;
+use serde::{Deserialize, Serialize};
 
 pub struct SandboxPlugin;
 
 impl Plugin for SandboxPlugin {
     fn build(&self, app: &mut App) {
+        app.register_scene::<SandboxScene>();
         // app.add_systems(
         //     Update,
         //     (leaf_particles,).run_if(super::scene_type_exists::<SandboxScene>),
@@ -21,10 +23,12 @@ impl Plugin for SandboxPlugin {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SandboxScene;
 
 impl Scene for SandboxScene {
+    const NAME: &'static str = "sandbox";
+
     fn spawn(&self, root: &mut EntityCommands) {
         let entity = root.id();
         root.commands().queue(init(entity));
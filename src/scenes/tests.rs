@@ -0,0 +1,76 @@
+use super::*;
+use crate::flags::StoryFlags;
+use crate::inventory::Inventory;
+use crate::player::Player;
+use crate::reset::WorldLoop;
+use crate::save::{SaveFile, SavePlugin};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum TestScene {
+    Hall,
+    Attic,
+}
+
+impl Scene for TestScene {
+    const NAME: &'static str = "test";
+
+    fn spawn(&self, root: &mut EntityCommands) {
+        root.with_child((Player, Transform::from_xyz(8., 8., 1.)));
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SavePlugin))
+        .insert_resource(SaveFile(
+            std::env::temp_dir()
+                .join("annual-scenes-test.sav")
+                .to_string_lossy()
+                .into(),
+        ))
+        .init_resource::<VisitedScenes>()
+        .init_resource::<Inventory>()
+        .init_resource::<WorldLoop>()
+        .init_resource::<StoryFlags>()
+        .register_scene::<TestScene>();
+    app.update();
+    app
+}
+
+fn scene(app: &mut App) -> SceneId {
+    let world = app.world_mut();
+    world.query::<&ActiveScene>().single(world).0.clone()
+}
+
+#[test]
+fn parse_scene_addresses() {
+    assert_eq!(
+        SceneId::parse("test:Attic"),
+        SceneId::new(&TestScene::Attic)
+    );
+    assert_eq!(
+        SceneId::parse(" test : Hall "),
+        SceneId::new(&TestScene::Hall)
+    );
+    assert_eq!(SceneId::parse("park"), SceneId::parse("park:()"));
+}
+
+#[test]
+fn switch_scene_by_address() {
+    let mut app = app();
+    app.world_mut().spawn(SceneRoot::new(TestScene::Hall));
+    app.world_mut().flush();
+
+    app.world_mut().commands().switch_scene("test:Attic");
+    app.world_mut().flush();
+    assert_eq!(scene(&mut app), SceneId::new(&TestScene::Attic));
+
+    let world = app.world_mut();
+    assert_eq!(world.query::<&Player>().iter(world).count(), 1);
+    assert!(world.resource::<VisitedScenes>().contains(&TestScene::Hall));
+
+    // Unknown scenes leave the active scene in place.
+    app.world_mut().commands().switch_scene("cellar");
+    app.world_mut().flush();
+    assert_eq!(scene(&mut app), SceneId::new(&TestScene::Attic));
+}